@@ -2,173 +2,6104 @@
 
 #[ink::contract]
 mod crash_game_casino {
+    use ink::env::call::{build_call, ExecutionInput, Selector};
+    use ink::prelude::string::String;
+    use ink::prelude::vec::Vec;
     use ink::storage::Mapping;
     use parity_scale_codec::{Encode, Decode};
     use scale_info::TypeInfo;
     use ink::storage::traits::StorageLayout;
 
+    const CONTRACT_VERSION: u32 = 1;
+    const SCHEMA_VERSION: u32 = 1;
+    const MULTIPLIER_BASE: u32 = 100;
+    const MULTIPLIER_GROWTH_PER_BLOCK: u32 = 10;
+    const DEFAULT_MAX_MULTIPLIER: u32 = 100 * MULTIPLIER_BASE;
+    const DEFAULT_CRASH_PROBABILITY_BPS: u16 = 0;
+    const HOUSE_EDGE_PERCENT: u64 = 1;
+    const MAX_PAGE_SIZE: u8 = 50;
+    const MAX_AUTO_CASHOUTS: usize = 50;
+    const MAX_PENDING_EXITS: usize = 8;
+    const MAX_TRACKS: u64 = 8;
+    const MAX_BATCH_SIZE: usize = 50;
+    const MAX_REFERRAL_DEPTH: u32 = 16;
+    const DEFAULT_STALL_TIMEOUT: u32 = 14_400;
+    const MAX_GAME_INTERVAL: u32 = 100_800;
+    const DEFAULT_LIQUIDATION_THRESHOLD: u32 = 432_000;
+    const DEFAULT_JACKPOT_PROBABILITY_BPS: u16 = 100;
+    const SWEEP_DELAY: u32 = 100_800;
+    const MAX_GRACE_BLOCKS: u32 = 600;
+    const MAX_FEE_TIERS: usize = 8;
+    const MAX_SOLVENCY_SCAN: u32 = 500;
+    const LEADERBOARD_SIZE: usize = 10;
+    const CONVERSION_RATE_BASE: Balance = 1_000_000;
+
     #[derive(Encode, Decode, Clone, Debug, Default, PartialEq, Eq, TypeInfo, StorageLayout)]
     pub struct Player {
         token_balance: Balance,
+        bonus_tokens: Balance,
         exited: bool,
+        auto_cashout_price: Option<Balance>,
+        paid_out: Balance,
+        total_deposited: Balance,
+        entry_block: u32,
+        pending_exits: Vec<PendingExit>,
+        settled: bool,
+    }
+
+    #[derive(Encode, Decode, Clone, Debug, Default, PartialEq, Eq, TypeInfo, StorageLayout)]
+    pub struct PendingExit {
+        tokens: Balance,
+        multiplier: u32,
+        value: Balance,
+    }
+
+    #[derive(Encode, Decode, Clone, Debug, Default, PartialEq, Eq, TypeInfo, StorageLayout)]
+    pub struct EntrySchedule {
+        remaining: u8,
+        amount_each: Balance,
     }
 
     #[derive(Encode, Decode, Clone, Debug, Default, PartialEq, Eq, TypeInfo, StorageLayout)]
     pub struct Game {
         id: u64,
+        track_id: u64,
+        mode: GameMode,
+        state: GameState,
         start_block: u32,
         price: Balance,
         crashed: bool,
+        cancelled: bool,
+        crash_multiplier: u32,
+        final_multiplier: u32,
+        crash_block: u32,
+        resolution_block: u32,
+        reveal_block: u32,
+        reveal_timestamp: u64,
+        start_timestamp: u64,
+        start_nonce: u64,
+        crash_probability_bps: u16,
+        crash_threshold: u32,
+        resolution_entropy: u32,
+        fee_override_bps: Option<u16>,
+        resolver: Option<AccountId>,
+        forfeited: Balance,
         game_pool: Balance,
+        jackpot_share: Balance,
+        open_tokens: Balance,
+        open_deposits: Balance,
+        active_players: u32,
+        open_positions: u32,
+        reserved: Balance,
+        schedules_run: bool,
     }
 
-    #[ink(storage)]
-    pub struct CrashCasino {
-        owner: AccountId,
+    #[derive(
+        Encode, Decode, Clone, Copy, Debug, Default, PartialEq, Eq, TypeInfo, StorageLayout,
+    )]
+    pub enum GameMode {
+        #[default]
+        CashOut,
+        LastStanding,
+    }
+
+    #[derive(
+        Encode, Decode, Clone, Copy, Debug, Default, PartialEq, Eq, TypeInfo, StorageLayout,
+    )]
+    pub enum GameState {
+        #[default]
+        Open,
+        Resolving,
+        Crashed,
+        Cashed,
+        Cancelled,
+    }
+
+    #[derive(Encode, Decode, Clone, Debug, Default, PartialEq, Eq, TypeInfo, StorageLayout)]
+    pub struct Track {
+        id: u64,
         game_interval: u32,
         last_game_block: u32,
         current_game_id: u64,
+        mode: GameMode,
+    }
+
+    #[derive(Encode, Decode, Clone, Debug, Default, PartialEq, Eq, TypeInfo, StorageLayout)]
+    pub struct PlayerStats {
+        games_entered: u64,
+        games_exited: u64,
+        total_wagered: Balance,
+        total_won: Balance,
+        loss_streak: u32,
+    }
+
+    #[derive(Encode, Decode, Clone, Debug, Default, PartialEq, Eq, TypeInfo)]
+    pub struct GameSummary {
+        id: u64,
+        crashed: bool,
+        game_pool: Balance,
+    }
+
+    #[derive(Encode, Decode, Clone, Debug, Default, PartialEq, Eq, TypeInfo)]
+    pub struct CasinoStats {
+        total_games: u64,
+        casino_pool: Balance,
+        house_revenue: Balance,
+        total_volume: Balance,
+        active_players: u32,
+    }
+
+    #[derive(Encode, Decode, Clone, Debug, PartialEq, Eq, TypeInfo)]
+    pub struct FairnessProof {
+        game_id: u64,
+        resolution_block: u32,
+        resolver: Option<AccountId>,
+        commitment: Option<Hash>,
+        seed: Option<[u8; 32]>,
+        salt: Vec<u8>,
+        resolution_entropy: u32,
+    }
+
+    #[derive(Encode, Decode, Clone, Debug, PartialEq, Eq, TypeInfo)]
+    pub enum Psp22Error {
+        Custom(String),
+        InsufficientBalance,
+        InsufficientAllowance,
+        ZeroRecipientAddress,
+        ZeroSenderAddress,
+        SafeTransferCheckFailed(String),
+    }
+
+    /// Errors returned by every fallible message and the constructor.
+    ///
+    /// Variants are SCALE-encoded as their declaration index, which is the stable code
+    /// clients match on. New variants are only ever appended.
+    ///
+    /// | Code | Variant |
+    /// |------|---------|
+    /// | 0 | `NoActiveGame` |
+    /// | 1 | `GameCrashed` |
+    /// | 2 | `ZeroFunds` |
+    /// | 3 | `BetTooSmall` |
+    /// | 4 | `BetTooLarge` |
+    /// | 5 | `InvalidBetLimits` |
+    /// | 6 | `InvalidFee` |
+    /// | 7 | `InsufficientRevenue` |
+    /// | 8 | `TransferFailed` |
+    /// | 9 | `ReserveBreached` |
+    /// | 10 | `NotPendingOwner` |
+    /// | 11 | `Paused` |
+    /// | 12 | `NotInGame` |
+    /// | 13 | `AlreadyExited` |
+    /// | 14 | `InvalidTarget` |
+    /// | 15 | `AutoCashoutQueueFull` |
+    /// | 16 | `InsufficientFunds` |
+    /// | 17 | `SeedAlreadyCommitted` |
+    /// | 18 | `GameAlreadyStarted` |
+    /// | 19 | `NoSeedCommitted` |
+    /// | 20 | `RevealTooEarly` |
+    /// | 21 | `SeedMismatch` |
+    /// | 22 | `SeedNotRevealed` |
+    /// | 23 | `SeedAlreadyRevealed` |
+    /// | 24 | `TokenNotConfigured` |
+    /// | 25 | `NativeBetsDisabled` |
+    /// | 26 | `TokenTransferFailed` |
+    /// | 27 | `GameCancelled` |
+    /// | 28 | `GameNotCancelled` |
+    /// | 29 | `GameResolved` |
+    /// | 30 | `GameExpired` |
+    /// | 31 | `NotKeeper` |
+    /// | 32 | `TrackNotFound` |
+    /// | 33 | `TooManyTracks` |
+    /// | 34 | `InsufficientTokens` |
+    /// | 35 | `ExitQueueFull` |
+    /// | 36 | `InvalidMultiplier` |
+    /// | 37 | `Overflow` |
+    /// | 38 | `Blacklisted` |
+    /// | 39 | `NotAllowed` |
+    /// | 40 | `SelfReferral` |
+    /// | 41 | `ReferralLoop` |
+    /// | 42 | `NoReferralRewards` |
+    /// | 43 | `NotStalled` |
+    /// | 44 | `InvalidInterval` |
+    /// | 45 | `InvalidPrice` |
+    /// | 46 | `InsufficientReserves` |
+    /// | 47 | `BatchTooLarge` |
+    /// | 48 | `NoWinnings` |
+    /// | 49 | `InvalidProbability` |
+    /// | 50 | `GameActive` |
+    /// | 51 | `Cooldown` |
+    /// | 52 | `Liquidated` |
+    /// | 53 | `OwnerActive` |
+    /// | 54 | `GameNotCrashed` |
+    /// | 55 | `GameTooRecent` |
+    /// | 56 | `NoGameStarted` |
+    /// | 57 | `GamePoolFull` |
+    /// | 58 | `ExitsDisabled` |
+    /// | 59 | `InvalidWinnerCount` |
+    /// | 60 | `GameFull` |
+    /// | 61 | `InconsistentAccounting` |
+    /// | 62 | `ExposureLimit` |
+    /// | 63 | `InsufficientBalance` |
+    /// | 64 | `InvalidFeeTiers` |
+    /// | 65 | `PositionUnsettled` |
+    /// | 66 | `InvalidAddress` |
+    /// | 67 | `EntryNotOpen` |
+    /// | 68 | `GameChanged` |
+    /// | 69 | `NotOwner` |
+    /// | 70 | `NotAdmin` |
+    /// | 71 | `InvalidPriceScale` |
+    /// | 72 | `InvalidConversionRate` |
+    /// | 73 | `PoolBelowMinimum` |
+    /// | 74 | `ContractsNotAllowed` |
+    /// | 75 | `InvalidSchedule` |
+    /// | 76 | `ScheduleQueueFull` |
+    #[derive(Encode, Decode, Clone, Copy, Debug, PartialEq, Eq, TypeInfo)]
+    pub enum Error {
+        NoActiveGame,
+        GameCrashed,
+        ZeroFunds,
+        BetTooSmall,
+        BetTooLarge,
+        InvalidBetLimits,
+        InvalidFee,
+        InsufficientRevenue,
+        TransferFailed,
+        ReserveBreached,
+        NotPendingOwner,
+        Paused,
+        NotInGame,
+        AlreadyExited,
+        InvalidTarget,
+        AutoCashoutQueueFull,
+        InsufficientFunds,
+        /// The game already has a seed commitment; the owner cannot swap it once made.
+        SeedAlreadyCommitted,
+        /// Commitments must be made before the game starts so the owner cannot pick a
+        /// seed after seeing the bets.
+        GameAlreadyStarted,
+        /// Nothing was committed for this game, so there is nothing to reveal.
+        NoSeedCommitted,
+        /// Seeds may only be revealed once the game's betting window has closed.
+        RevealTooEarly,
+        /// The revealed seed does not hash to the commitment and is rejected.
+        SeedMismatch,
+        /// The game has a commitment that has not been revealed. Resolution waits on
+        /// the owner, who can therefore stall a game but not bias its outcome.
+        SeedNotRevealed,
+        /// The game's seed is already revealed; a second reveal would re-roll its outcome.
+        SeedAlreadyRevealed,
+        TokenNotConfigured,
+        NativeBetsDisabled,
+        TokenTransferFailed,
+        GameCancelled,
+        GameNotCancelled,
+        /// The game has resolved; its positions can only be settled now.
+        GameResolved,
+        GameExpired,
+        NotKeeper,
+        TrackNotFound,
+        TooManyTracks,
+        InsufficientTokens,
+        ExitQueueFull,
+        InvalidMultiplier,
+        Overflow,
+        Blacklisted,
+        NotAllowed,
+        SelfReferral,
+        ReferralLoop,
+        NoReferralRewards,
+        NotStalled,
+        InvalidInterval,
+        InvalidPrice,
+        InsufficientReserves,
+        BatchTooLarge,
+        NoWinnings,
+        InvalidProbability,
+        GameActive,
+        Cooldown,
+        Liquidated,
+        OwnerActive,
+        GameNotCrashed,
+        GameTooRecent,
+        /// The track has not been ticked since it was created. Games are never started
+        /// implicitly; the first `tick` opens the track's first game.
+        NoGameStarted,
+        GamePoolFull,
+        ExitsDisabled,
+        InvalidWinnerCount,
+        GameFull,
+        /// An internal balance would underflow. Indicates inconsistent accounting; the
+        /// call is rejected rather than letting the value wrap.
+        InconsistentAccounting,
+        ExposureLimit,
+        InsufficientBalance,
+        InvalidFeeTiers,
+        PositionUnsettled,
+        InvalidAddress,
+        EntryNotOpen,
+        GameChanged,
+        NotOwner,
+        NotAdmin,
+        InvalidPriceScale,
+        InvalidConversionRate,
+        PoolBelowMinimum,
+        ContractsNotAllowed,
+        InvalidSchedule,
+        ScheduleQueueFull,
+    }
+
+    #[ink(event)]
+    pub struct GameStarted {
+        #[ink(topic)]
+        game_id: u64,
+        start_block: u32,
+        price: Balance,
+    }
+
+    #[ink(event)]
+    pub struct GameCrashed {
+        #[ink(topic)]
+        game_id: u64,
+        crash_multiplier: u32,
+        final_pool: Balance,
+        forfeited: Balance,
+        forced: bool,
+        resolution_entropy: u32,
+    }
+
+    #[ink(event)]
+    pub struct GameCancelled {
+        #[ink(topic)]
+        game_id: u64,
+        refundable: Balance,
+    }
+
+    #[ink(event)]
+    pub struct PlayerRefunded {
+        #[ink(topic)]
+        player: AccountId,
+        #[ink(topic)]
+        game_id: u64,
+        amount: Balance,
+    }
+
+    #[ink(event)]
+    pub struct PayoutCapped {
+        #[ink(topic)]
+        game_id: u64,
+        #[ink(topic)]
+        player: AccountId,
+        uncapped: Balance,
+        capped: Balance,
+    }
+
+    #[ink(event)]
+    pub struct GameSwept {
+        #[ink(topic)]
+        game_id: u64,
+        forfeited: Balance,
+        cleared: u32,
+        remaining: u32,
+    }
+
+    #[ink(event)]
+    pub struct MaxMultiplierReached {
+        #[ink(topic)]
+        game_id: u64,
+        multiplier: u32,
+        holders: u32,
+    }
+
+    #[ink(event)]
+    pub struct MultiplierTick {
+        #[ink(topic)]
+        game_id: u64,
+        multiplier: u32,
+    }
+
+    #[ink(event)]
+    pub struct JackpotWon {
+        #[ink(topic)]
+        game_id: u64,
+        #[ink(topic)]
+        winner: AccountId,
+        amount: Balance,
+    }
+
+    #[ink(event)]
+    pub struct EntriesScheduled {
+        #[ink(topic)]
+        player: AccountId,
+        #[ink(topic)]
+        track_id: u64,
+        count: u8,
+        amount_each: Balance,
+    }
+
+    #[ink(event)]
+    pub struct ScheduleCancelled {
+        #[ink(topic)]
+        player: AccountId,
+        #[ink(topic)]
+        track_id: u64,
+        refunded: Balance,
+    }
+
+    #[ink(event)]
+    pub struct RebateCredited {
+        #[ink(topic)]
+        player: AccountId,
+        loss_streak: u32,
+        amount: Balance,
+    }
+
+    #[ink(event)]
+    pub struct KeeperRewarded {
+        #[ink(topic)]
+        keeper: AccountId,
+        amount: Balance,
+    }
+
+    #[ink(event)]
+    pub struct PoolFunded {
+        #[ink(topic)]
+        from: AccountId,
+        amount: Balance,
+    }
+
+    #[ink(event)]
+    pub struct RebatePoolFunded {
+        #[ink(topic)]
+        from: AccountId,
+        amount: Balance,
+    }
+
+    #[ink(event)]
+    pub struct WinningsWithdrawn {
+        #[ink(topic)]
+        player: AccountId,
+        amount: Balance,
+    }
+
+    #[ink(event)]
+    pub struct BalanceDeposited {
+        #[ink(topic)]
+        player: AccountId,
+        amount: Balance,
+    }
+
+    #[ink(event)]
+    pub struct BalanceWithdrawn {
+        #[ink(topic)]
+        player: AccountId,
+        amount: Balance,
+    }
+
+    #[ink(event)]
+    pub struct PlayerEntered {
+        #[ink(topic)]
+        player: AccountId,
+        #[ink(topic)]
+        game_id: u64,
+        amount: Balance,
+        tokens: Balance,
+    }
+
+    #[ink(event)]
+    pub struct ExitRequested {
+        #[ink(topic)]
+        player: AccountId,
+        #[ink(topic)]
+        game_id: u64,
+        multiplier: u32,
+        value: Balance,
+    }
+
+    #[ink(event)]
+    pub struct PlayerExited {
+        #[ink(topic)]
+        player: AccountId,
+        #[ink(topic)]
+        game_id: u64,
+        payout: Balance,
+    }
+
+    #[ink(event)]
+    pub struct PendingOwnershipTransfer {
+        #[ink(topic)]
+        owner: AccountId,
+        #[ink(topic)]
+        pending_owner: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct OwnershipTransferred {
+        #[ink(topic)]
+        previous_owner: AccountId,
+        #[ink(topic)]
+        new_owner: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct Paused {
+        #[ink(topic)]
+        by: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct Unpaused {
+        #[ink(topic)]
+        by: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct ConfigChanged {
+        #[ink(topic)]
+        field: String,
+        old_value: Balance,
+        new_value: Balance,
+    }
+
+    #[ink(event)]
+    pub struct AddressChanged {
+        #[ink(topic)]
+        field: String,
+        old_address: Option<AccountId>,
+        new_address: Option<AccountId>,
+    }
+
+    #[ink(event)]
+    pub struct AccessChanged {
+        #[ink(topic)]
+        account: AccountId,
+        field: String,
+        granted: bool,
+    }
+
+    #[ink(event)]
+    pub struct GameIntervalChanged {
+        #[ink(topic)]
+        track_id: u64,
+        old_interval: u32,
+        new_interval: u32,
+    }
+
+    #[ink(event)]
+    pub struct GameModeChanged {
+        #[ink(topic)]
+        track_id: u64,
+        old_mode: GameMode,
+        new_mode: GameMode,
+    }
+
+    #[ink(event)]
+    pub struct FeeTiersChanged {
+        old_tiers: Vec<(Balance, u16)>,
+        new_tiers: Vec<(Balance, u16)>,
+    }
+
+    #[ink(event)]
+    pub struct GameFeeOverridden {
+        #[ink(topic)]
+        game_id: u64,
+        fee_bps: Option<u16>,
+    }
+
+    #[ink(event)]
+    pub struct StartDeferred {
+        #[ink(topic)]
+        track_id: u64,
+        casino_pool: Balance,
+        required: Balance,
+    }
+
+    #[ink(event)]
+    pub struct LiquidationTriggered {
+        #[ink(topic)]
+        by: AccountId,
+        owner_last_active: u32,
+    }
+
+    #[ink(storage)]
+    pub struct CrashCasino {
+        schema_version: u32,
+        owner: AccountId,
+        pending_owner: Option<AccountId>,
+        owner_last_active: u32,
+        liquidation_threshold: u32,
+        liquidated: bool,
+        admins: Mapping<AccountId, bool>,
+        treasury: AccountId,
+        token: Option<AccountId>,
+        keeper: Option<AccountId>,
+        keeper_reward: Balance,
+        track_count: u64,
+        game_count: u64,
+        oldest_game_id: u64,
+        history_limit: u64,
+        nonce: u64,
+        paused: bool,
+        auto_restart: bool,
+        block_contracts: bool,
+        restricted: bool,
+        stall_timeout: u32,
+        max_grace_blocks: u32,
+        bet_cooldown: u32,
+        entry_delay: u32,
         casino_pool: Balance,
+        base_price: Balance,
+        price_scale: Balance,
+        min_bet: Balance,
+        max_bet: Balance,
+        house_fee_bps: u16,
+        fee_tiers: Vec<(Balance, u16)>,
+        early_bonus_blocks: u32,
+        early_bonus_bps: u16,
+        holding_bonus_bps: u16,
+        referral_bps: u16,
+        max_payout_per_player: Balance,
+        max_game_pool: Balance,
+        max_account_exposure: Balance,
+        max_participants: u32,
+        last_standing_winners: u32,
+        max_multiplier: u32,
+        crash_probability_bps: u16,
+        house_revenue: Balance,
+        jackpot_bps: u16,
+        jackpot_probability_bps: u16,
+        jackpot_pool: Balance,
+        rebate_threshold: u32,
+        rebate_amount: Balance,
+        rebate_pool: Balance,
+        refund_reserve: Balance,
+        claim_reserve: Balance,
+        total_volume: Balance,
+        total_paid_out: Balance,
+        rounding_dust: Balance,
+        rounding_buffer: Balance,
+        conversion_rate: Balance,
+        min_pool_to_start: Balance,
+        leaderboard: Vec<(AccountId, Balance)>,
+        owner_seeded: Balance,
+        tracks: Mapping<u64, Track>,
         games: Mapping<u64, Game>,
         players: Mapping<(u64, AccountId), Player>,
+        participant_counts: Mapping<u64, u32>,
+        participants: Mapping<(u64, u32), AccountId>,
+        player_stats: Mapping<AccountId, PlayerStats>,
+        auto_cashouts: Mapping<u64, Vec<AccountId>>,
+        schedules: Mapping<(u64, AccountId), EntrySchedule>,
+        scheduled: Mapping<u64, Vec<AccountId>>,
+        seed_commits: Mapping<u64, Hash>,
+        fee_overrides: Mapping<u64, u16>,
+        revealed_seeds: Mapping<u64, [u8; 32]>,
+        blacklist: Mapping<AccountId, bool>,
+        allowlist: Mapping<AccountId, bool>,
+        referrers: Mapping<AccountId, AccountId>,
+        referral_balances: Mapping<AccountId, Balance>,
+        pending_withdrawals: Mapping<AccountId, Balance>,
+        wallets: Mapping<AccountId, Balance>,
+        last_bet_block: Mapping<AccountId, u32>,
     }
 
     impl CrashCasino {
         #[ink(constructor)]
-        pub fn new(game_interval: u32) -> Self {
+        pub fn new(
+            game_interval: u32,
+            token: Option<AccountId>,
+            base_price: Balance,
+            price_scale: Balance,
+        ) -> Result<Self, Error> {
+            if base_price == 0 {
+                return Err(Error::InvalidPrice);
+            }
+            if !Self::valid_price_scale(price_scale) {
+                return Err(Error::InvalidPriceScale);
+            }
+            if !Self::valid_interval(game_interval) {
+                return Err(Error::InvalidInterval);
+            }
             let owner = Self::env().caller();
             let block = Self::env().block_number();
-            Self {
+            let mut tracks = Mapping::default();
+            tracks.insert(
+                0,
+                &Track {
+                    id: 0,
+                    game_interval,
+                    last_game_block: block,
+                    current_game_id: 0,
+                    mode: GameMode::CashOut,
+                },
+            );
+            Ok(Self {
+                schema_version: SCHEMA_VERSION,
                 owner,
-                game_interval,
-                last_game_block: block,
-                current_game_id: 0,
+                pending_owner: None,
+                owner_last_active: block,
+                liquidation_threshold: DEFAULT_LIQUIDATION_THRESHOLD,
+                liquidated: false,
+                admins: Mapping::default(),
+                treasury: owner,
+                token,
+                keeper: None,
+                keeper_reward: 0,
+                track_count: 1,
+                game_count: 0,
+                oldest_game_id: 1,
+                history_limit: 0,
+                nonce: 0,
+                paused: false,
+                auto_restart: true,
+                block_contracts: false,
+                restricted: false,
+                stall_timeout: DEFAULT_STALL_TIMEOUT,
+                max_grace_blocks: 0,
+                bet_cooldown: 0,
+                entry_delay: 0,
                 casino_pool: 0,
+                base_price,
+                price_scale,
+                min_bet: 0,
+                max_bet: Balance::MAX,
+                house_fee_bps: 0,
+                fee_tiers: Vec::new(),
+                early_bonus_blocks: 0,
+                early_bonus_bps: 0,
+                holding_bonus_bps: 0,
+                referral_bps: 0,
+                max_payout_per_player: 0,
+                max_game_pool: 0,
+                max_account_exposure: 0,
+                max_participants: 0,
+                last_standing_winners: 1,
+                max_multiplier: DEFAULT_MAX_MULTIPLIER,
+                crash_probability_bps: DEFAULT_CRASH_PROBABILITY_BPS,
+                house_revenue: 0,
+                jackpot_bps: 0,
+                jackpot_probability_bps: DEFAULT_JACKPOT_PROBABILITY_BPS,
+                jackpot_pool: 0,
+                rebate_threshold: 0,
+                rebate_amount: 0,
+                rebate_pool: 0,
+                refund_reserve: 0,
+                claim_reserve: 0,
+                total_volume: 0,
+                total_paid_out: 0,
+                rounding_dust: 0,
+                rounding_buffer: 0,
+                conversion_rate: CONVERSION_RATE_BASE,
+                min_pool_to_start: 0,
+                leaderboard: Vec::new(),
+                owner_seeded: 0,
+                tracks,
                 games: Mapping::default(),
                 players: Mapping::default(),
+                participant_counts: Mapping::default(),
+                participants: Mapping::default(),
+                player_stats: Mapping::default(),
+                auto_cashouts: Mapping::default(),
+                schedules: Mapping::default(),
+                scheduled: Mapping::default(),
+                seed_commits: Mapping::default(),
+                fee_overrides: Mapping::default(),
+                revealed_seeds: Mapping::default(),
+                blacklist: Mapping::default(),
+                allowlist: Mapping::default(),
+                referrers: Mapping::default(),
+                referral_balances: Mapping::default(),
+                pending_withdrawals: Mapping::default(),
+                wallets: Mapping::default(),
+                last_bet_block: Mapping::default(),
+            })
+        }
+
+        fn valid_interval(game_interval: u32) -> bool {
+            game_interval > 0 && game_interval <= MAX_GAME_INTERVAL
+        }
+
+        fn valid_price_scale(mut price_scale: Balance) -> bool {
+            if price_scale == 0 {
+                return false;
+            }
+            while price_scale.is_multiple_of(10) {
+                price_scale /= 10;
+            }
+            price_scale == 1
+        }
+
+        fn only_owner(&mut self) -> Result<(), Error> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner);
+            }
+            self.owner_last_active = self.env().block_number();
+            Ok(())
+        }
+
+        fn ensure_not_contract(&self, account: AccountId) -> Result<(), Error> {
+            if self.block_contracts && self.env().is_contract(&account) {
+                return Err(Error::ContractsNotAllowed);
+            }
+            Ok(())
+        }
+
+        fn ensure_valid_address(account: AccountId) -> Result<(), Error> {
+            if account == AccountId::from([0u8; 32]) {
+                return Err(Error::InvalidAddress);
+            }
+            Ok(())
+        }
+
+        fn only_admin(&mut self) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if !self.is_admin(caller) {
+                return Err(Error::NotAdmin);
+            }
+            if caller == self.owner {
+                self.owner_last_active = self.env().block_number();
             }
+            Ok(())
+        }
+
+        fn config_changed(&self, field: &str, old_value: Balance, new_value: Balance) {
+            self.env().emit_event(ConfigChanged {
+                field: String::from(field),
+                old_value,
+                new_value,
+            });
         }
 
-        fn only_owner(&self) {
-            assert_eq!(self.env().caller(), self.owner, "Not contract owner");
+        fn access_changed(&self, account: AccountId, field: &str, granted: bool) {
+            self.env().emit_event(AccessChanged {
+                account,
+                field: String::from(field),
+                granted,
+            });
         }
 
-        fn pseudo_random(&self, salt: &[u8]) -> u8 {
+        fn pseudo_random(&self, salt: &[u8]) -> u32 {
             let entropy = self.env().hash_bytes::<ink::env::hash::Blake2x256>(salt);
-            entropy.as_ref()[0]
+            u32::from_be_bytes([entropy[0], entropy[1], entropy[2], entropy[3]])
+        }
+
+        fn crash_point(entropy: u32) -> u32 {
+            let range = u32::MAX as u64 + 1;
+            let point = MULTIPLIER_BASE as u64 * (100 - HOUSE_EDGE_PERCENT) * range
+                / (100 * (range - entropy as u64));
+            point.min(u32::MAX as u64) as u32
+        }
+
+        fn interval_of(&self, game: &Game) -> u32 {
+            self.tracks
+                .get(game.track_id)
+                .map_or(0, |track| track.game_interval)
+        }
+
+        fn multiplier_at(&self, game: &Game, block: u32) -> u32 {
+            let elapsed = block
+                .saturating_sub(game.start_block)
+                .min(self.interval_of(game));
+            MULTIPLIER_BASE
+                .saturating_add(elapsed.saturating_mul(MULTIPLIER_GROWTH_PER_BLOCK))
+                .min(self.max_multiplier)
+        }
+
+        fn price_at(game: &Game, multiplier: u32) -> Balance {
+            game.price.saturating_mul(multiplier as Balance) / MULTIPLIER_BASE as Balance
+        }
+
+        fn live_price(&self, game: &Game) -> Balance {
+            Self::price_at(game, self.multiplier_at(game, self.env().block_number()))
+        }
+
+        /// The first multiplier at which the game's price reaches `target`.
+        fn multiplier_for(game: &Game, target: Balance) -> u32 {
+            target
+                .saturating_mul(MULTIPLIER_BASE as Balance)
+                .div_ceil(game.price)
+                .min(u32::MAX as Balance) as u32
+        }
+
+        fn current_games(&self) -> impl Iterator<Item = Game> + '_ {
+            (0..self.track_count)
+                .filter_map(|track_id| self.tracks.get(track_id))
+                .filter_map(|track| self.games.get(track.current_game_id))
+                .filter(|game| game.final_multiplier == 0 && !game.cancelled)
+        }
+
+        /// Token amounts round down, so an entrant never receives more tokens than the
+        /// stake buys. The unrepresentable remainder is recorded as rounding dust.
+        fn tokens_for(&self, amount: Balance, price: Balance) -> Result<Balance, Error> {
+            amount
+                .checked_mul(self.price_scale)
+                .and_then(|scaled| scaled.checked_div(price))
+                .ok_or(Error::Overflow)
+        }
+
+        /// Values round down, so payouts never exceed the exact value of the tokens.
+        fn value_of(&self, tokens: Balance, price: Balance) -> Result<Balance, Error> {
+            tokens
+                .checked_mul(price)
+                .map(|value| value / self.price_scale)
+                .ok_or(Error::Overflow)
+        }
+
+        fn reserved_liability(&self) -> Balance {
+            self.current_games()
+                .map(|game| {
+                    self.value_of(game.open_tokens, self.live_price(&game))
+                        .unwrap_or(Balance::MAX)
+                })
+                .fold(
+                    self.refund_reserve.saturating_add(self.claim_reserve),
+                    Balance::saturating_add,
+                )
+        }
+
+        fn current_game_of(&self, track_id: u64) -> Result<Game, Error> {
+            let track = self.tracks.get(track_id).ok_or(Error::TrackNotFound)?;
+            if track.current_game_id == 0 {
+                return Err(Error::NoGameStarted);
+            }
+            self.games
+                .get(track.current_game_id)
+                .ok_or(Error::NoActiveGame)
+        }
+
+        fn ensure_can_tick(&self) -> Result<(), Error> {
+            if self.liquidated {
+                return Err(Error::Liquidated);
+            }
+            if self.paused {
+                return Err(Error::Paused);
+            }
+            if self
+                .keeper
+                .is_some_and(|keeper| keeper != self.env().caller())
+            {
+                return Err(Error::NotKeeper);
+            }
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn tick(&mut self) -> Result<(), Error> {
+            self.ensure_can_tick()?;
+            for track_id in 0..self.track_count {
+                match self.advance_track(track_id) {
+                    Err(Error::SeedNotRevealed) => continue,
+                    result => result?,
+                }
+            }
+            Ok(())
         }
 
         #[ink(message)]
-        pub fn tick(&mut self) {
+        pub fn tick_track(&mut self, track_id: u64) -> Result<(), Error> {
+            self.ensure_can_tick()?;
+            self.advance_track(track_id)
+        }
+
+        fn advance_track(&mut self, track_id: u64) -> Result<(), Error> {
+            let mut track = self.tracks.get(track_id).ok_or(Error::TrackNotFound)?;
+            let current_block = self.env().block_number();
+            if current_block >= track.last_game_block + track.game_interval {
+                if !self.grace_elapsed(&track) {
+                    return Ok(());
+                }
+                if self.seed_commits.contains(track.current_game_id)
+                    && !self.revealed_seeds.contains(track.current_game_id)
+                {
+                    return Err(Error::SeedNotRevealed);
+                }
+                if self.end_previous_game_if_active(&track)? {
+                    self.reward_keeper();
+                }
+                if self.auto_restart {
+                    self.start_new_game(&mut track, current_block);
+                }
+            } else if let Some(game) = self.games.get(track.current_game_id) {
+                if !game.crashed && !game.cancelled {
+                    self.env().emit_event(MultiplierTick {
+                        game_id: game.id,
+                        multiplier: self.multiplier_at(&game, current_block),
+                    });
+                    self.run_scheduled_entries(track_id);
+                }
+            }
+            Ok(())
+        }
+
+        fn grace_elapsed(&mut self, track: &Track) -> bool {
+            let Some(mut game) = self.games.get(track.current_game_id) else {
+                return true;
+            };
+            if game.crashed || game.cancelled {
+                return true;
+            }
+            if game.open_tokens == 0 {
+                return true;
+            }
             let current_block = self.env().block_number();
-            if current_block >= self.last_game_block + self.game_interval {
-                self.end_previous_game_if_active();
-                self.start_new_game();
+            if game.crash_block == 0 {
+                let salt = [
+                    game.id.to_be_bytes().as_ref(),
+                    &current_block.to_be_bytes(),
+                    &self.env().block_timestamp().to_be_bytes(),
+                ]
+                .concat();
+                let grace = self.pseudo_random(&salt) % (self.max_grace_blocks + 1);
+                game.crash_block = current_block + grace;
+                game.state = GameState::Resolving;
+                self.games.insert(game.id, &game);
             }
+            current_block >= game.crash_block
         }
 
-        fn start_new_game(&mut self) {
+        #[ink(message)]
+        pub fn catch_up(&mut self, track_id: u64, max_games: u8) -> Result<u8, Error> {
+            self.ensure_can_tick()?;
             let current_block = self.env().block_number();
-            let game_id = self.current_game_id + 1;
-            let new_game = Game {
+            let mut processed = 0;
+            while processed < max_games {
+                let mut track = self.tracks.get(track_id).ok_or(Error::TrackNotFound)?;
+                let due = track.last_game_block + track.game_interval;
+                if current_block < due || !self.grace_elapsed(&track) {
+                    break;
+                }
+                if self.seed_commits.contains(track.current_game_id)
+                    && !self.revealed_seeds.contains(track.current_game_id)
+                {
+                    break;
+                }
+                if self.end_previous_game_if_active(&track)? {
+                    self.reward_keeper();
+                }
+                processed += 1;
+                if !self.auto_restart || !self.start_new_game(&mut track, due) {
+                    break;
+                }
+            }
+            Ok(processed)
+        }
+
+        /// Leaves the track idle, to be retried on a later tick, while the pool is short.
+        fn start_new_game(&mut self, track: &mut Track, start_block: u32) -> bool {
+            if self.casino_pool < self.min_pool_to_start {
+                self.env().emit_event(StartDeferred {
+                    track_id: track.id,
+                    casino_pool: self.casino_pool,
+                    required: self.min_pool_to_start,
+                });
+                return false;
+            }
+            let game_id = self.game_count + 1;
+            self.nonce += 1;
+            let mut new_game = Game {
                 id: game_id,
-                start_block: current_block,
-                price: 1_000_000_000_000,
+                track_id: track.id,
+                mode: track.mode,
+                state: GameState::Open,
+                start_block,
+                price: self.base_price,
                 crashed: false,
+                cancelled: false,
+                crash_multiplier: 0,
+                final_multiplier: 0,
+                crash_block: 0,
+                resolution_block: 0,
+                reveal_block: 0,
+                reveal_timestamp: 0,
+                start_timestamp: self.env().block_timestamp(),
+                start_nonce: self.nonce,
+                crash_probability_bps: self.crash_probability_bps,
+                crash_threshold: 0,
+                resolution_entropy: 0,
+                fee_override_bps: self.fee_overrides.take(game_id),
+                resolver: None,
+                forfeited: 0,
                 game_pool: 0,
+                jackpot_share: 0,
+                open_tokens: 0,
+                open_deposits: 0,
+                active_players: 0,
+                open_positions: 0,
+                reserved: 0,
+                schedules_run: false,
             };
+            // Rolled before anyone can enter, so nothing a bettor or the resolving caller
+            // does later can move it. A committed seed rolls it when revealed instead.
+            if !self.seed_commits.contains(game_id) {
+                self.roll_crash(&mut new_game, None);
+            }
             self.games.insert(game_id, &new_game);
-            self.current_game_id = game_id;
-            self.last_game_block = current_block;
+            self.game_count = game_id;
+            track.current_game_id = game_id;
+            track.last_game_block = start_block;
+            self.tracks.insert(track.id, track);
+            self.evict_history();
+            self.env().emit_event(GameStarted {
+                game_id,
+                start_block,
+                price: new_game.price,
+            });
+            self.run_scheduled_entries(track.id);
+            true
         }
 
-        fn end_previous_game_if_active(&mut self) {
-            if self.current_game_id == 0 {
+        /// Runs once per game, on the first tick after the entry delay has passed. An entry
+        /// that fails its checks is skipped for this game and retried on the next; only a
+        /// successful entry consumes one of the scheduled games.
+        fn run_scheduled_entries(&mut self, track_id: u64) {
+            let queue = self.scheduled.get(track_id).unwrap_or_default();
+            if queue.is_empty() {
                 return;
             }
-            let mut game = self.games.get(self.current_game_id).unwrap();
-            if game.crashed {
+            let Ok(mut game) = self.current_game_of(track_id) else {
+                return;
+            };
+            let block = self.env().block_number();
+            if game.schedules_run
+                || (self.entry_delay > 0 && block <= game.start_block + self.entry_delay)
+            {
                 return;
             }
-            let salt = [
-                self.current_game_id.to_be_bytes().as_ref(),
-                &self.env().block_number().to_be_bytes(),
-                self.env().caller().as_ref(),
+            game.schedules_run = true;
+            self.games.insert(game.id, &game);
+            let mut retained = Vec::new();
+            for account in queue {
+                let Some(mut schedule) = self.schedules.get((track_id, account)) else {
+                    continue;
+                };
+                if self.ensure_not_contract(account).is_ok()
+                    && self
+                        .place_bet(track_id, account, schedule.amount_each)
+                        .is_ok()
+                {
+                    schedule.remaining -= 1;
+                }
+                if schedule.remaining == 0 {
+                    self.schedules.remove((track_id, account));
+                } else {
+                    self.schedules.insert((track_id, account), &schedule);
+                    retained.push(account);
+                }
+            }
+            self.scheduled.insert(track_id, &retained);
+        }
+
+        /// Without a seed the salt holds only what was fixed when the game started, so the
+        /// block a resolution lands in has no say in the outcome. A committed seed is mixed
+        /// with the block it was revealed in, which comes only after bets have closed, so
+        /// not even the committer knows the outcome while entries are open.
+        fn resolution_salt(game: &Game, seed: Option<[u8; 32]>) -> Vec<u8> {
+            let window = [
+                game.id.to_be_bytes().as_ref(),
+                &game.start_block.to_be_bytes(),
+                &game.start_timestamp.to_be_bytes(),
+                &game.start_nonce.to_be_bytes(),
             ]
                 .concat();
-            let chance = self.pseudo_random(&salt) % 2;
-            if chance == 0 {
+            match seed {
+                Some(seed) => [
+                    window.as_slice(),
+                    &seed,
+                    &game.reveal_block.to_be_bytes(),
+                    &game.reveal_timestamp.to_be_bytes(),
+                ]
+                .concat(),
+                None => window,
+            }
+        }
+
+        /// Rolled once per game. The raw entropy is recorded alongside the threshold it
+        /// decides, so anyone can rehash the salt and check both once they are published.
+        fn roll_crash(&self, game: &mut Game, seed: Option<[u8; 32]>) {
+            let salt = Self::resolution_salt(game, seed);
+            let entropy = self.pseudo_random(&salt);
+            let bust = self.pseudo_random(&[salt.as_slice(), b"bust"].concat());
+            game.resolution_entropy = entropy;
+            game.crash_threshold = if bust % 10_000 < game.crash_probability_bps as u32 {
+                MULTIPLIER_BASE
+            } else {
+                Self::crash_point(entropy)
+            };
+        }
+
+        /// Evicts from the front of the retained window only, so it stays contiguous.
+        /// A game that is still live or holds claimable positions blocks eviction. Its
+        /// positions are cleared first, at most a batch per call, and the game record
+        /// goes only once none are left.
+        fn evict_history(&mut self) {
+            if self.history_limit == 0 {
+                return;
+            }
+            let mut budget = MAX_BATCH_SIZE as u32;
+            for _ in 0..MAX_BATCH_SIZE {
+                let retained = self.game_count + 1 - self.oldest_game_id;
+                if retained <= self.history_limit {
+                    return;
+                }
+                if let Some(mut game) = self.games.get(self.oldest_game_id) {
+                    let current = self
+                        .tracks
+                        .get(game.track_id)
+                        .is_some_and(|track| track.current_game_id == game.id);
+                    let settled = match game.state {
+                        GameState::Crashed => game.reserved == 0,
+                        GameState::Cashed | GameState::Cancelled => game.open_positions == 0,
+                        GameState::Open | GameState::Resolving => false,
+                    };
+                    if current || !settled {
+                        return;
+                    }
+                    let Ok((cleared, remaining)) = self.clear_participants(&mut game, budget)
+                    else {
+                        return;
+                    };
+                    budget -= cleared;
+                    if remaining > 0 {
+                        return;
+                    }
+                    self.games.remove(game.id);
+                }
+                self.oldest_game_id += 1;
+            }
+        }
+        /// Returns whether a game with participants was resolved; only those resolutions
+        /// earn the keeper reward, so ticking an idle track pays nothing.
+        fn end_previous_game_if_active(&mut self, track: &Track) -> Result<bool, Error> {
+            if track.current_game_id == 0 {
+                return Ok(false);
+            }
+            let mut game = self
+                .games
+                .get(track.current_game_id)
+                .ok_or(Error::NoActiveGame)?;
+            if game.final_multiplier > 0 || game.cancelled {
+                return Ok(false);
+            }
+            let seed = self.revealed_seeds.get(game.id);
+            game.resolution_block = self.env().block_number();
+            game.resolver = match seed {
+                Some(_) => None,
+                None => Some(self.env().caller()),
+            };
+            self.games.insert(game.id, &game);
+            let salt = Self::resolution_salt(&game, seed);
+            let reached = self.multiplier_at(&game, self.env().block_number());
+            if game.mode == GameMode::LastStanding {
+                // The auto-cashout targets rank the field instead of paying out.
+                self.settle_last_standing(&mut game, reached)?;
+                self.draw_jackpot(&game, &salt);
+                return Ok(self.participant_counts.get(game.id).unwrap_or(0) > 0);
+            }
+            self.process_auto_cashouts(&mut game, reached)?;
+            if game.crash_threshold <= reached {
+                let crash_multiplier = game.crash_threshold;
                 game.crashed = true;
-                self.games.insert(self.current_game_id, &game);
+                game.state = GameState::Crashed;
+                game.crash_multiplier = crash_multiplier;
+                game.final_multiplier = crash_multiplier;
+                game.forfeited =
+                    self.value_of(game.open_tokens, Self::price_at(&game, crash_multiplier))?;
+                self.games.insert(game.id, &game);
+                self.env().emit_event(GameCrashed {
+                    game_id: game.id,
+                    crash_multiplier,
+                    final_pool: game.game_pool,
+                    forfeited: game.forfeited,
+                    forced: false,
+                    resolution_entropy: game.resolution_entropy,
+                });
+            } else {
+                game.state = GameState::Cashed;
+                game.final_multiplier = reached;
+                let owed = self
+                    .value_of(game.open_tokens, Self::price_at(&game, reached))?
+                    .checked_mul(10_000 + self.holding_bonus_bps as Balance)
+                    .ok_or(Error::Overflow)?
+                    / 10_000;
+                game.reserved += owed;
+                self.claim_reserve += owed;
+                self.games.insert(game.id, &game);
+                if reached >= self.max_multiplier {
+                    self.env().emit_event(MaxMultiplierReached {
+                        game_id: game.id,
+                        multiplier: reached,
+                        holders: game.active_players,
+                    });
+                }
             }
+            self.draw_jackpot(&game, &salt);
+            Ok(self.participant_counts.get(game.id).unwrap_or(0) > 0)
         }
 
-        #[ink(message, payable)]
-        pub fn enter_game(&mut self) {
-            let game_id = self.current_game_id;
-            let mut game = self.games.get(game_id).expect("No active game");
-            assert!(!game.crashed, "Game already crashed");
+        fn reward_keeper(&mut self) {
+            let free = self.get_free_liquidity();
+            if self.keeper_reward == 0 || free < self.keeper_reward {
+                return;
+            }
+            let keeper = self.env().caller();
+            self.casino_pool -= self.keeper_reward;
+            let pending = self.pending_withdrawals.get(keeper).unwrap_or(0);
+            self.pending_withdrawals
+                .insert(keeper, &(pending + self.keeper_reward));
+            self.env().emit_event(KeeperRewarded {
+                keeper,
+                amount: self.keeper_reward,
+            });
+        }
 
-            let caller = self.env().caller();
-            let amount = self.env().transferred_value();
-            assert!(amount > 0, "No funds sent");
+        /// Each position bails out at its auto-cashout target, or rides to the end of the
+        /// window without one. The N positions still in when the crash triggers split the
+        /// pool; if fewer are still in, those that bailed out last fill the remaining
+        /// places. Ties go to the earlier entrant, so entering late never outranks anyone.
+        fn settle_last_standing(&mut self, game: &mut Game, reached: u32) -> Result<(), Error> {
+            let crashes = game.crash_threshold <= reached;
+            let count = self.participant_counts.get(game.id).unwrap_or(0);
+            let mut entrants = Vec::new();
+            for index in 0..count {
+                let Some(account) = self.participants.get((game.id, index)) else {
+                    continue;
+                };
+                let Some(player) = self.players.get((game.id, account)) else {
+                    continue;
+                };
+                let bail_out = player.auto_cashout_price.map_or(reached, |target| {
+                    Self::multiplier_for(game, target).min(reached)
+                });
+                entrants.push((account, player, bail_out.min(game.crash_threshold)));
+            }
+            let mut survivors: Vec<(usize, u32)> = entrants
+                .iter()
+                .enumerate()
+                .map(|(index, (_, _, held_until))| (index, *held_until))
+                .collect();
+            survivors.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+            survivors.truncate(self.last_standing_winners as usize);
 
-            let tokens = amount * 1_000_000_000_000 / game.price;
-            let key = (game_id, caller);
-            let mut player = self.players.get(key).unwrap_or_default();
-            player.token_balance += tokens;
-            player.exited = false;
-            self.players.insert(key, &player);
+            if crashes {
+                game.crashed = true;
+                game.state = GameState::Crashed;
+                game.crash_multiplier = game.crash_threshold;
+                game.final_multiplier = game.crash_threshold;
+            } else {
+                game.state = GameState::Cashed;
+                game.final_multiplier = reached;
+            }
+            // The jackpot's cut of each stake never reached the casino pool.
+            let gross = match survivors.len() as Balance {
+                0 => 0,
+                winners => {
+                    let pot = game.game_pool.saturating_sub(game.jackpot_share);
+                    pot.min(self.casino_pool) / winners
+                }
+            };
+            if survivors.is_empty() {
+                game.forfeited = game.game_pool;
+            }
+            for (index, (account, mut player, _)) in entrants.into_iter().enumerate() {
+                if !player.exited {
+                    game.active_players -= 1;
+                }
+                let won = survivors.iter().any(|(winner, _)| *winner == index);
+                let share = if won {
+                    let fee = Self::fee_on(gross, self.fee_bps_for(game, &player));
+                    let share = self.converted(gross - fee)?;
+                    self.casino_pool = self
+                        .casino_pool
+                        .checked_sub(fee + share)
+                        .ok_or(Error::InconsistentAccounting)?;
+                    self.house_revenue += fee;
+                    share
+                } else {
+                    0
+                };
+                player.token_balance = 0;
+                player.bonus_tokens = 0;
+                player.total_deposited = 0;
+                player.exited = true;
+                player.settled = true;
+                if won {
+                    player.paid_out += gross;
+                }
+                self.players.insert((game.id, account), &player);
+                if !won {
+                    self.record_loss(account);
+                    continue;
+                }
+                let mut stats = self.player_stats.get(account).unwrap_or_default();
+                stats.games_exited += 1;
+                stats.total_won += share;
+                stats.loss_streak = 0;
+                self.player_stats.insert(account, &stats);
+                self.update_leaderboard(account, stats.total_won);
+                let pending = self.pending_withdrawals.get(account).unwrap_or(0);
+                self.pending_withdrawals.insert(account, &(pending + share));
+                self.total_paid_out += share;
+                self.env().emit_event(PlayerExited {
+                    player: account,
+                    game_id: game.id,
+                    payout: share,
+                });
+            }
+            game.open_tokens = 0;
+            game.open_deposits = 0;
+            game.open_positions = 0;
+            self.games.insert(game.id, game);
+            if crashes {
+                self.env().emit_event(GameCrashed {
+                    game_id: game.id,
+                    crash_multiplier: game.crash_multiplier,
+                    final_pool: game.game_pool,
+                    forfeited: game.forfeited,
+                    forced: false,
+                    resolution_entropy: game.resolution_entropy,
+                });
+            }
+            Ok(())
+        }
 
-            game.game_pool += amount;
-            self.games.insert(game_id, &game);
-            self.casino_pool += amount;
+        /// Counts a loss towards the account's streak. Once the streak passes the threshold
+        /// the rebate is credited and the streak starts over.
+        fn record_loss(&mut self, account: AccountId) {
+            let mut stats = self.player_stats.get(account).unwrap_or_default();
+            stats.loss_streak += 1;
+            self.player_stats.insert(account, &stats);
+            if self.rebate_threshold == 0 || stats.loss_streak <= self.rebate_threshold {
+                return;
+            }
+            let amount = self.rebate_amount.min(self.rebate_pool);
+            if amount == 0 {
+                return;
+            }
+            self.rebate_pool -= amount;
+            let pending = self.pending_withdrawals.get(account).unwrap_or(0);
+            self.pending_withdrawals
+                .insert(account, &(pending + amount));
+            self.env().emit_event(RebateCredited {
+                player: account,
+                loss_streak: stats.loss_streak,
+                amount,
+            });
+            stats.loss_streak = 0;
+            self.player_stats.insert(account, &stats);
         }
 
-        #[ink(message)]
-        pub fn exit_game(&mut self) {
-            let game_id = self.current_game_id;
-            let mut game = self.games.get(game_id).expect("No active game");
-            let caller = self.env().caller();
-            let key = (game_id, caller);
-            let mut player = self.players.get(key).expect("Not in game");
-            assert!(!player.exited, "Already exited");
-            assert!(!game.crashed, "Game crashed, too late!");
+        fn draw_jackpot(&mut self, game: &Game, salt: &[u8]) {
+            let participants = self.participant_counts.get(game.id).unwrap_or(0);
+            if self.jackpot_pool == 0 || participants == 0 {
+                return;
+            }
+            let entropy = self.pseudo_random(&[salt, b"jackpot"].concat());
+            if entropy % 10_000 >= self.jackpot_probability_bps as u32 {
+                return;
+            }
+            let index = (entropy / 10_000) % participants;
+            let Some(winner) = self.participants.get((game.id, index)) else {
+                return;
+            };
+            let amount = self.jackpot_pool;
+            self.jackpot_pool = 0;
+            let pending = self.pending_withdrawals.get(winner).unwrap_or(0);
+            self.pending_withdrawals.insert(winner, &(pending + amount));
+            self.env().emit_event(JackpotWon {
+                game_id: game.id,
+                winner,
+                amount,
+            });
+        }
 
-            let payout = player.token_balance * game.price / 1_000_000_000_000;
-            assert!(self.casino_pool >= payout, "Casino has insufficient funds");
+        /// Credits `uncapped`, less the payout cap and the fee and converted into the
+        /// settlement asset, to the account's pending withdrawals. Checks the pool before
+        /// touching any state.
+        fn pay_out(
+            &mut self,
+            game: &Game,
+            account: AccountId,
+            player: &mut Player,
+            uncapped: Balance,
+        ) -> Result<Balance, Error> {
+            let gross = self.capped_payout(player, uncapped);
+            let fee = Self::fee_on(gross, self.fee_bps_for(game, player));
+            let payout = self.converted(gross - fee)?;
+            let debit = fee.checked_add(payout).ok_or(Error::Overflow)?;
+            self.casino_pool = self
+                .casino_pool
+                .checked_sub(debit)
+                .ok_or(Error::InconsistentAccounting)?;
+            if gross < uncapped {
+                self.env().emit_event(PayoutCapped {
+                    game_id: game.id,
+                    player: account,
+                    uncapped,
+                    capped: gross,
+                });
+            }
+            self.house_revenue += fee;
+            player.paid_out += gross;
+            let mut stats = self.player_stats.get(account).unwrap_or_default();
+            stats.total_won += payout;
+            stats.loss_streak = 0;
+            self.player_stats.insert(account, &stats);
+            self.update_leaderboard(account, stats.total_won);
+            self.total_paid_out += payout;
+            let pending = self.pending_withdrawals.get(account).unwrap_or(0);
+            self.pending_withdrawals
+                .insert(account, &(pending + payout));
+            self.env().emit_event(PlayerExited {
+                player: account,
+                game_id: game.id,
+                payout,
+            });
+            Ok(payout)
+        }
 
-            self.env().transfer(caller, payout).expect("Transfer failed");
-            self.casino_pool -= payout;
-            player.exited = true;
-            self.players.insert(key, &player);
+        /// The token-weighted average of the blocks the position was bought at, rounded up
+        /// so a top-up never earns more holding time than its tokens were held.
+        fn weighted_entry(player: &Player, tokens: Balance, block: u32) -> u32 {
+            let held = player.token_balance;
+            if held == 0 {
+                return block;
+            }
+            let weighted = (player.entry_block as Balance)
+                .saturating_mul(held)
+                .saturating_add((block as Balance).saturating_mul(tokens));
+            weighted.div_ceil(held.saturating_add(tokens)) as u32
         }
 
-        #[ink(message)]
-        pub fn set_game_interval(&mut self, new_interval: u32) {
-            self.only_owner();
-            self.game_interval = new_interval;
+        fn holding_weighted(
+            &self,
+            game: &Game,
+            player: &Player,
+            value: Balance,
+            until: u32,
+        ) -> Result<Balance, Error> {
+            let interval = self.interval_of(game);
+            if self.holding_bonus_bps == 0 || interval == 0 {
+                return Ok(value);
+            }
+            let held = until.saturating_sub(player.entry_block).min(interval);
+            let bonus_bps =
+                self.holding_bonus_bps as Balance * held as Balance / interval as Balance;
+            value
+                .checked_mul(10_000 + bonus_bps)
+                .map(|weighted| weighted / 10_000)
+                .ok_or(Error::Overflow)
         }
 
-        #[ink(message)]
-        pub fn get_current_game(&self) -> Option<Game> {
-            self.games.get(self.current_game_id)
+        fn capped_payout(&self, player: &Player, uncapped: Balance) -> Balance {
+            if self.max_payout_per_player > 0 {
+                uncapped.min(self.max_payout_per_player.saturating_sub(player.paid_out))
+            } else {
+                uncapped
+            }
         }
 
-        #[ink(message)]
-        pub fn get_my_status(&self) -> Option<Player> {
-            let key = (self.current_game_id, self.env().caller());
-            self.players.get(key)
+        fn fee_bps_for(&self, game: &Game, player: &Player) -> u16 {
+            if let Some(fee_bps) = game.fee_override_bps {
+                return fee_bps;
+            }
+            self.fee_tiers
+                .iter()
+                .rev()
+                .find(|(threshold, _)| player.total_deposited >= *threshold)
+                .map_or(self.house_fee_bps, |(_, bps)| *bps)
         }
 
-        #[ink(message)]
-        pub fn get_casino_pool(&self) -> Balance {
-            self.casino_pool
+        fn update_leaderboard(&mut self, account: AccountId, total_won: Balance) {
+            self.leaderboard.retain(|(entry, _)| *entry != account);
+            let position = self
+                .leaderboard
+                .iter()
+                .position(|(_, won)| *won < total_won)
+                .unwrap_or(self.leaderboard.len());
+            if position < LEADERBOARD_SIZE {
+                self.leaderboard.insert(position, (account, total_won));
+                self.leaderboard.truncate(LEADERBOARD_SIZE);
+            }
         }
 
-        #[ink(message)]
-        pub fn get_block(&self) -> u32 {
+        /// Fees round up, keeping the net payout rounded down like every other payout.
+        fn fee_on(gross: Balance, fee_bps: u16) -> Balance {
+            (gross * fee_bps as Balance).div_ceil(10_000)
+        }
+
+        /// Translates a net payout into the settlement asset, rounding down.
+        fn converted(&self, net: Balance) -> Result<Balance, Error> {
+            net.checked_mul(self.conversion_rate)
+                .map(|scaled| scaled / CONVERSION_RATE_BASE)
+                .ok_or(Error::Overflow)
+        }
+
+        /// The early bonus only pays on tokens that ride to resolution, so exiting `tokens`
+        /// forfeits their proportional share of it.
+        fn bonus_share(player: &Player, tokens: Balance) -> Result<Balance, Error> {
+            if tokens == player.token_balance {
+                return Ok(player.bonus_tokens);
+            }
+            player
+                .bonus_tokens
+                .checked_mul(tokens)
+                .map(|share| share / player.token_balance)
+                .ok_or(Error::Overflow)
+        }
+
+        /// Locks `tokens` in at `multiplier` and reserves their value until the position is
+        /// settled. The position only counts as exited once no tokens are left riding.
+        fn request_exit(
+            &mut self,
+            game: &mut Game,
+            account: AccountId,
+            player: &mut Player,
+            tokens: Balance,
+            multiplier: u32,
+            price: Balance,
+        ) -> Result<(), Error> {
+            if game.mode == GameMode::LastStanding {
+                return Err(Error::ExitsDisabled);
+            }
+            if player.pending_exits.len() >= MAX_PENDING_EXITS {
+                return Err(Error::ExitQueueFull);
+            }
+            let value = self.value_of(tokens, price)?;
+            let value = self.holding_weighted(game, player, value, self.env().block_number())?;
+            let bonus = Self::bonus_share(player, tokens)?;
+            player.token_balance -= tokens;
+            player.bonus_tokens -= bonus;
+            game.open_tokens -= tokens + bonus;
+            if player.token_balance == 0 {
+                player.exited = true;
+                game.active_players -= 1;
+                self.count_exit(account);
+            }
+            player.pending_exits.push(PendingExit {
+                tokens,
+                multiplier,
+                value,
+            });
+            game.reserved += value;
+            self.claim_reserve += value;
+            self.players.insert((game.id, account), player);
+            self.games.insert(game.id, game);
+            self.env().emit_event(ExitRequested {
+                player: account,
+                game_id: game.id,
+                multiplier,
+                value,
+            });
+            Ok(())
+        }
+
+        /// Pays every claim the resolved game honours in one payout: exits requested below
+        /// the crash point and, when the game survived its window, the tokens still riding
+        /// at its final multiplier. Both were reserved beforehand, so the owner cannot
+        /// withdraw the liquidity they are owed.
+        fn settle(
+            &mut self,
+            game: &mut Game,
+            account: AccountId,
+            player: &mut Player,
+        ) -> Result<Balance, Error> {
+            let mut uncapped: Balance = 0;
+            let mut released: Balance = 0;
+            for exit in &player.pending_exits {
+                released = released.saturating_add(exit.value);
+                if !game.crashed || exit.multiplier < game.crash_multiplier {
+                    uncapped = uncapped.checked_add(exit.value).ok_or(Error::Overflow)?;
+                }
+            }
+            let riding = !player.exited && !game.crashed;
+            if riding {
+                let price = Self::price_at(game, game.final_multiplier);
+                let value = self.value_of(player.token_balance + player.bonus_tokens, price)?;
+                let value = self.holding_weighted(game, player, value, game.resolution_block)?;
+                uncapped = uncapped.checked_add(value).ok_or(Error::Overflow)?;
+                released = released.saturating_add(value);
+            }
+            let payout = if uncapped > 0 {
+                self.pay_out(game, account, player, uncapped)?
+            } else {
+                0
+            };
+            // Losses are counted here rather than at the crash, so every position is
+            // counted exactly once however many entered the game.
+            if player.paid_out == 0 {
+                self.record_loss(account);
+                self.credit_referrer(account, player.total_deposited)?;
+            }
+            self.release_claim(game, released);
+            player.pending_exits.clear();
+            if !player.exited {
+                game.open_tokens -= player.token_balance + player.bonus_tokens;
+                game.active_players -= 1;
+                player.token_balance = 0;
+                player.bonus_tokens = 0;
+                player.exited = true;
+                if riding {
+                    self.count_exit(account);
+                }
+            }
+            game.open_deposits -= player.total_deposited;
+            player.total_deposited = 0;
+            self.close_position(game, player);
+            self.players.insert((game.id, account), player);
+            self.games.insert(game.id, game);
+            Ok(payout)
+        }
+
+        /// The referrer of a losing account earns `referral_bps` of the forfeited stake,
+        /// taken from the pool the stake was forfeited to.
+        fn credit_referrer(&mut self, account: AccountId, lost: Balance) -> Result<(), Error> {
+            let Some(referrer) = self.referrers.get(account) else {
+                return Ok(());
+            };
+            let share = lost * self.referral_bps as Balance / 10_000;
+            if share == 0 {
+                return Ok(());
+            }
+            self.casino_pool = self
+                .casino_pool
+                .checked_sub(share)
+                .ok_or(Error::InconsistentAccounting)?;
+            let balance = self.referral_balances.get(referrer).unwrap_or(0);
+            self.referral_balances.insert(referrer, &(balance + share));
+            Ok(())
+        }
+
+        fn count_exit(&mut self, account: AccountId) {
+            let mut stats = self.player_stats.get(account).unwrap_or_default();
+            stats.games_exited += 1;
+            self.player_stats.insert(account, &stats);
+        }
+
+        fn release_claim(&mut self, game: &mut Game, amount: Balance) {
+            let amount = amount.min(game.reserved);
+            game.reserved -= amount;
+            self.claim_reserve = self.claim_reserve.saturating_sub(amount);
+        }
+
+        /// Marks the position as final. The last one to close releases whatever the game
+        /// still holds in reserve.
+        fn close_position(&mut self, game: &mut Game, player: &mut Player) {
+            player.settled = true;
+            game.open_positions -= 1;
+            if game.open_positions == 0 {
+                let reserved = game.reserved;
+                self.release_claim(game, reserved);
+            }
+        }
+
+        /// A target pays when the price reached it within the window and strictly below
+        /// the crash point.
+        fn process_auto_cashouts(&mut self, game: &mut Game, reached: u32) -> Result<(), Error> {
+            let queue = self.auto_cashouts.take(game.id).unwrap_or_default();
+            for account in queue {
+                let Some(mut player) = self.players.get((game.id, account)) else {
+                    continue;
+                };
+                if player.exited {
+                    continue;
+                }
+                let Some(target) = player.auto_cashout_price else {
+                    continue;
+                };
+                let multiplier = Self::multiplier_for(game, target);
+                if multiplier <= reached && multiplier < game.crash_threshold {
+                    match self.cash_out(game, account, &mut player, target) {
+                        Err(Error::InconsistentAccounting) => continue,
+                        result => result?,
+                    };
+                }
+            }
+            Ok(())
+        }
+
+        /// Pays riding tokens out immediately. Only resolution uses this, for auto-cashout
+        /// targets, since only then is the crash point known.
+        fn cash_out(
+            &mut self,
+            game: &mut Game,
+            account: AccountId,
+            player: &mut Player,
+            price: Balance,
+        ) -> Result<Balance, Error> {
+            if game.mode == GameMode::LastStanding {
+                return Err(Error::ExitsDisabled);
+            }
+            let value = self.value_of(player.token_balance, price)?;
+            let uncapped = self.holding_weighted(game, player, value, game.resolution_block)?;
+            let payout = self.pay_out(game, account, player, uncapped)?;
+            game.open_tokens -= player.token_balance + player.bonus_tokens;
+            game.open_deposits -= player.total_deposited;
+            game.active_players -= 1;
+            player.token_balance = 0;
+            player.bonus_tokens = 0;
+            player.total_deposited = 0;
+            player.exited = true;
+            self.count_exit(account);
+            if player.pending_exits.is_empty() {
+                self.close_position(game, player);
+            }
+            self.players.insert((game.id, account), player);
+            self.games.insert(game.id, game);
+            Ok(payout)
+        }
+
+        #[ink(message, payable)]
+        pub fn enter_game(&mut self, track_id: u64) -> Result<(), Error> {
+            if self.token.is_some() {
+                return Err(Error::NativeBetsDisabled);
+            }
+            let caller = self.env().caller();
+            self.ensure_not_contract(caller)?;
+            self.place_bet(track_id, caller, self.env().transferred_value())
+        }
+
+        #[ink(message, payable)]
+        pub fn enter_game_for(
+            &mut self,
+            track_id: u64,
+            beneficiary: AccountId,
+        ) -> Result<(), Error> {
+            if self.token.is_some() {
+                return Err(Error::NativeBetsDisabled);
+            }
+            Self::ensure_valid_address(beneficiary)?;
+            self.ensure_not_contract(self.env().caller())?;
+            self.place_bet(track_id, beneficiary, self.env().transferred_value())
+        }
+
+        #[ink(message, payable)]
+        pub fn enter_game_checked(
+            &mut self,
+            track_id: u64,
+            expected_game_id: u64,
+        ) -> Result<(), Error> {
+            let track = self.tracks.get(track_id).ok_or(Error::TrackNotFound)?;
+            if track.current_game_id != expected_game_id {
+                return Err(Error::GameChanged);
+            }
+            self.enter_game(track_id)
+        }
+
+        #[ink(message, payable)]
+        pub fn enter_game_with_referrer(
+            &mut self,
+            track_id: u64,
+            referrer: AccountId,
+        ) -> Result<(), Error> {
+            self.register_referrer(referrer)?;
+            self.enter_game(track_id)
+        }
+
+        /// Token-mode counterpart of `enter_game_with_referrer`.
+        #[ink(message)]
+        pub fn enter_game_with_token_and_referrer(
+            &mut self,
+            track_id: u64,
+            referrer: AccountId,
+            amount: Balance,
+        ) -> Result<(), Error> {
+            self.register_referrer(referrer)?;
+            self.enter_game_with_token(track_id, amount)
+        }
+
+        /// Records the referrer of a caller who has never played. Returning players keep
+        /// whatever referrer they had, or none.
+        fn register_referrer(&mut self, referrer: AccountId) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if referrer == caller {
+                return Err(Error::SelfReferral);
+            }
+            if self.player_stats.contains(caller) || self.referrers.contains(caller) {
+                return Ok(());
+            }
+            let mut ancestor = self.referrers.get(referrer);
+            for _ in 0..MAX_REFERRAL_DEPTH {
+                match ancestor {
+                    Some(account) if account == caller => return Err(Error::ReferralLoop),
+                    Some(account) => ancestor = self.referrers.get(account),
+                    None => break,
+                }
+            }
+            if ancestor.is_some() {
+                return Err(Error::ReferralLoop);
+            }
+            self.referrers.insert(caller, &referrer);
+            Ok(())
+        }
+
+        #[ink(message, payable)]
+        pub fn deposit_balance(&mut self) -> Result<(), Error> {
+            if self.token.is_some() {
+                return Err(Error::NativeBetsDisabled);
+            }
+            let amount = self.env().transferred_value();
+            if amount == 0 {
+                return Err(Error::ZeroFunds);
+            }
+            let caller = self.env().caller();
+            let balance = self.wallets.get(caller).unwrap_or(0);
+            self.wallets.insert(caller, &(balance + amount));
+            self.env().emit_event(BalanceDeposited {
+                player: caller,
+                amount,
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn enter_game_from_balance(
+            &mut self,
+            track_id: u64,
+            amount: Balance,
+        ) -> Result<(), Error> {
+            let caller = self.env().caller();
+            self.ensure_not_contract(caller)?;
+            self.debit_wallet(caller, amount)?;
+            self.place_bet(track_id, caller, amount)
+        }
+
+        #[ink(message)]
+        pub fn withdraw_balance(&mut self, amount: Balance) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if amount == 0 {
+                return Err(Error::ZeroFunds);
+            }
+            self.debit_wallet(caller, amount)?;
+            self.pay(caller, amount)?;
+            self.env().emit_event(BalanceWithdrawn {
+                player: caller,
+                amount,
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_wallet_balance(&self, account: AccountId) -> Balance {
+            self.wallets.get(account).unwrap_or(0)
+        }
+
+        /// Prepays `count` entries of `amount_each`, placed automatically in each new game
+        /// on the track once its entry window opens.
+        #[ink(message, payable)]
+        pub fn schedule_entries(
+            &mut self,
+            track_id: u64,
+            count: u8,
+            amount_each: Balance,
+        ) -> Result<(), Error> {
+            if self.token.is_some() {
+                return Err(Error::NativeBetsDisabled);
+            }
+            if !self.tracks.contains(track_id) {
+                return Err(Error::TrackNotFound);
+            }
+            if count == 0 || amount_each == 0 {
+                return Err(Error::ZeroFunds);
+            }
+            if amount_each < self.min_bet {
+                return Err(Error::BetTooSmall);
+            }
+            if amount_each > self.max_bet {
+                return Err(Error::BetTooLarge);
+            }
+            let total = amount_each
+                .checked_mul(count as Balance)
+                .ok_or(Error::Overflow)?;
+            let caller = self.env().caller();
+            self.ensure_not_contract(caller)?;
+            if self.env().transferred_value() != total
+                || self.schedules.contains((track_id, caller))
+            {
+                return Err(Error::InvalidSchedule);
+            }
+            let mut queue = self.scheduled.get(track_id).unwrap_or_default();
+            if queue.len() >= MAX_BATCH_SIZE {
+                return Err(Error::ScheduleQueueFull);
+            }
+            queue.push(caller);
+            self.scheduled.insert(track_id, &queue);
+            self.schedules.insert(
+                (track_id, caller),
+                &EntrySchedule {
+                    remaining: count,
+                    amount_each,
+                },
+            );
+            self.env().emit_event(EntriesScheduled {
+                player: caller,
+                track_id,
+                count,
+                amount_each,
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn cancel_scheduled_entries(&mut self, track_id: u64) -> Result<(), Error> {
+            let caller = self.env().caller();
+            let schedule = self
+                .schedules
+                .take((track_id, caller))
+                .ok_or(Error::InvalidSchedule)?;
+            let mut queue = self.scheduled.get(track_id).unwrap_or_default();
+            queue.retain(|account| *account != caller);
+            self.scheduled.insert(track_id, &queue);
+            let refunded = schedule.amount_each * schedule.remaining as Balance;
+            self.pay(caller, refunded)?;
+            self.env().emit_event(ScheduleCancelled {
+                player: caller,
+                track_id,
+                refunded,
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_entry_schedule(
+            &self,
+            track_id: u64,
+            account: AccountId,
+        ) -> Option<EntrySchedule> {
+            self.schedules.get((track_id, account))
+        }
+
+        fn debit_wallet(&mut self, account: AccountId, amount: Balance) -> Result<(), Error> {
+            let balance = self.wallets.get(account).unwrap_or(0);
+            if balance < amount {
+                return Err(Error::InsufficientBalance);
+            }
+            self.wallets.insert(account, &(balance - amount));
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn claim_referral_rewards(&mut self) -> Result<(), Error> {
+            let caller = self.env().caller();
+            let amount = self
+                .referral_balances
+                .take(caller)
+                .ok_or(Error::NoReferralRewards)?;
+            self.pay(caller, amount)
+        }
+
+        #[ink(message)]
+        pub fn enter_game_with_token(
+            &mut self,
+            track_id: u64,
+            amount: Balance,
+        ) -> Result<(), Error> {
+            self.token.ok_or(Error::TokenNotConfigured)?;
+            let caller = self.env().caller();
+            self.ensure_not_contract(caller)?;
+            self.place_bet(track_id, caller, amount)?;
+            self.collect(caller, amount)
+        }
+
+        /// Pulls `amount` of the configured token from `from`, who must have approved the
+        /// casino for at least that much.
+        fn collect(&self, from: AccountId, amount: Balance) -> Result<(), Error> {
+            let token = self.token.ok_or(Error::TokenNotConfigured)?;
+            build_call::<Environment>()
+                .call(token)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(ink::selector_bytes!(
+                        "PSP22::transfer_from"
+                    )))
+                    .push_arg(from)
+                    .push_arg(self.env().account_id())
+                    .push_arg(amount)
+                    .push_arg(Vec::<u8>::new()),
+                )
+                .returns::<Result<(), Psp22Error>>()
+                .try_invoke()
+                .map_err(|_| Error::TokenTransferFailed)?
+                .map_err(|_| Error::TokenTransferFailed)?
+                .map_err(|_| Error::TokenTransferFailed)
+        }
+
+        fn pay(&self, to: AccountId, amount: Balance) -> Result<(), Error> {
+            match self.token {
+                None => self
+                    .env()
+                    .transfer(to, amount)
+                    .map_err(|_| Error::TransferFailed),
+                Some(token) => build_call::<Environment>()
+                    .call(token)
+                    .exec_input(
+                        ExecutionInput::new(Selector::new(ink::selector_bytes!("PSP22::transfer")))
+                            .push_arg(to)
+                            .push_arg(amount)
+                            .push_arg(Vec::<u8>::new()),
+                    )
+                    .returns::<Result<(), Psp22Error>>()
+                    .try_invoke()
+                    .map_err(|_| Error::TokenTransferFailed)?
+                    .map_err(|_| Error::TokenTransferFailed)?
+                    .map_err(|_| Error::TokenTransferFailed),
+            }
+        }
+
+        fn place_bet(
+            &mut self,
+            track_id: u64,
+            caller: AccountId,
+            amount: Balance,
+        ) -> Result<(), Error> {
+            if self.liquidated {
+                return Err(Error::Liquidated);
+            }
+            if self.paused {
+                return Err(Error::Paused);
+            }
+            if self.blacklist.contains(caller) {
+                return Err(Error::Blacklisted);
+            }
+            if self.restricted && !self.allowlist.contains(caller) {
+                return Err(Error::NotAllowed);
+            }
+            let block = self.env().block_number();
+            if self
+                .last_bet_block
+                .get(caller)
+                .is_some_and(|last| block < last.saturating_add(self.bet_cooldown))
+            {
+                return Err(Error::Cooldown);
+            }
+            if self.casino_pool < self.reserved_liability() {
+                return Err(Error::InsufficientReserves);
+            }
+            let mut game = self.current_game_of(track_id)?;
+            let game_id = game.id;
+            if game.crashed {
+                return Err(Error::GameCrashed);
+            }
+            if game.cancelled {
+                return Err(Error::GameCancelled);
+            }
+            if block >= game.start_block + self.interval_of(&game) {
+                return Err(Error::GameExpired);
+            }
+            if self.entry_delay > 0 && block <= game.start_block + self.entry_delay {
+                return Err(Error::EntryNotOpen);
+            }
+
+            if amount == 0 {
+                return Err(Error::ZeroFunds);
+            }
+            if amount < self.min_bet {
+                return Err(Error::BetTooSmall);
+            }
+            if amount > self.max_bet {
+                return Err(Error::BetTooLarge);
+            }
+            if self.max_game_pool > 0 && game.game_pool.saturating_add(amount) > self.max_game_pool
+            {
+                return Err(Error::GamePoolFull);
+            }
+
+            let price = self.live_price(&game);
+            let tokens = self.tokens_for(amount, price)?;
+            let dust = amount - self.value_of(tokens, price)?;
+            let bonus = if block < game.start_block.saturating_add(self.early_bonus_blocks) {
+                tokens
+                    .checked_mul(self.early_bonus_bps as Balance)
+                    .ok_or(Error::Overflow)?
+                    / 10_000
+            } else {
+                0
+            };
+            let key = (game_id, caller);
+            let existing = self.players.get(key);
+            let deposited = existing.as_ref().map_or(0, |player| player.total_deposited);
+            if self.max_account_exposure > 0
+                && deposited.saturating_add(amount) > self.max_account_exposure
+            {
+                return Err(Error::ExposureLimit);
+            }
+            let mut stats = self.player_stats.get(caller).unwrap_or_default();
+            if existing.is_none() {
+                let index = self.participant_counts.get(game_id).unwrap_or(0);
+                if self.max_participants > 0 && index >= self.max_participants {
+                    return Err(Error::GameFull);
+                }
+                // LastStanding ranks every entrant in one pass at resolution.
+                if game.mode == GameMode::LastStanding && index >= MAX_BATCH_SIZE as u32 {
+                    return Err(Error::GameFull);
+                }
+                stats.games_entered += 1;
+                game.open_positions += 1;
+                self.participants.insert((game_id, index), &caller);
+                self.participant_counts.insert(game_id, &(index + 1));
+            }
+            stats.total_wagered += amount;
+            self.player_stats.insert(caller, &stats);
+
+            if existing.as_ref().is_none_or(|player| player.exited) {
+                game.active_players += 1;
+            }
+            let mut player = existing.unwrap_or_default();
+            player.entry_block = Self::weighted_entry(&player, tokens, block);
+            player.token_balance += tokens;
+            player.bonus_tokens += bonus;
+            player.total_deposited += amount;
+            player.exited = false;
+            self.players.insert(key, &player);
+
+            let jackpot_share = amount * self.jackpot_bps as Balance / 10_000;
+            game.game_pool += amount;
+            game.jackpot_share += jackpot_share;
+            game.open_tokens += tokens + bonus;
+            game.open_deposits += amount;
+            self.games.insert(game_id, &game);
+            self.jackpot_pool += jackpot_share;
+            self.casino_pool += amount - jackpot_share;
+            self.total_volume += amount;
+            self.last_bet_block.insert(caller, &block);
+            self.rounding_dust += dust;
+            self.env().emit_event(PlayerEntered {
+                player: caller,
+                game_id,
+                amount,
+                tokens,
+            });
+            Ok(())
+        }
+
+        /// Requests the exit, locking in the current multiplier. Nothing is paid until the
+        /// game resolves: the claim pays only if the crash point lies above that
+        /// multiplier, so an exit can never be placed with the outcome already known. Its
+        /// value is reserved until the position is settled.
+        #[ink(message)]
+        pub fn exit_game(&mut self, game_id: u64) -> Result<(), Error> {
+            let caller = self.env().caller();
+            let game = self.games.get(game_id).ok_or(Error::NoActiveGame)?;
+            if game.final_multiplier > 0 {
+                return self.settle_position(game_id, caller).map(|_| ());
+            }
+            let (mut game, mut player) = self.position_in(game, caller)?;
+            let multiplier = self.multiplier_at(&game, self.env().block_number());
+            let tokens = player.token_balance;
+            let price = Self::price_at(&game, multiplier);
+            self.request_exit(&mut game, caller, &mut player, tokens, multiplier, price)
+        }
+
+        fn open_position(
+            &self,
+            track_id: u64,
+            account: AccountId,
+        ) -> Result<(Game, Player), Error> {
+            let game = self.current_game_of(track_id)?;
+            self.position_in(game, account)
+        }
+
+        fn position_in(&self, game: Game, account: AccountId) -> Result<(Game, Player), Error> {
+            if game.crashed {
+                return Err(Error::GameCrashed);
+            }
+            if game.cancelled {
+                return Err(Error::GameCancelled);
+            }
+            if game.final_multiplier > 0 {
+                return Err(Error::GameResolved);
+            }
+            let player = self
+                .players
+                .get((game.id, account))
+                .ok_or(Error::NotInGame)?;
+            if player.exited {
+                return Err(Error::AlreadyExited);
+            }
+            Ok((game, player))
+        }
+
+        /// What an exit requested now would pay, provided the crash point turns out to lie
+        /// above the current multiplier. `None` in LastStanding games, which take no exits.
+        #[ink(message)]
+        pub fn quote_exit(&self, game_id: u64, account: AccountId) -> Option<Balance> {
+            let game = self.games.get(game_id)?;
+            if game.mode == GameMode::LastStanding {
+                return None;
+            }
+            let (game, player) = self.position_in(game, account).ok()?;
+            let value = self
+                .value_of(player.token_balance, self.live_price(&game))
+                .ok()?;
+            let uncapped = self
+                .holding_weighted(&game, &player, value, self.env().block_number())
+                .ok()?;
+            let gross = self.capped_payout(&player, uncapped);
+            let fee = Self::fee_on(gross, self.fee_bps_for(&game, &player));
+            let payout = self.converted(gross - fee).ok()?;
+            if self.casino_pool < fee.checked_add(payout)? {
+                return None;
+            }
+            Some(payout)
+        }
+
+        /// Mirrors the checks `exit_game` applies, so clients need not duplicate them.
+        /// Only an open game still inside its window counts; once the window closes the
+        /// position rides to resolution.
+        #[ink(message)]
+        pub fn can_exit(&self, game_id: u64, account: AccountId) -> bool {
+            let block = self.env().block_number();
+            self.games
+                .get(game_id)
+                .and_then(|game| self.position_in(game, account).ok())
+                .is_some_and(|(game, player)| {
+                    game.state == GameState::Open
+                        && block <= game.start_block + self.interval_of(&game)
+                        && game.mode != GameMode::LastStanding
+                        && player.pending_exits.len() < MAX_PENDING_EXITS
+                })
+        }
+
+        #[ink(message)]
+        pub fn withdraw_winnings(&mut self) -> Result<(), Error> {
+            let caller = self.env().caller();
+            let amount = self
+                .pending_withdrawals
+                .take(caller)
+                .ok_or(Error::NoWinnings)?;
+            self.pay(caller, amount)?;
+            self.env().emit_event(WinningsWithdrawn {
+                player: caller,
+                amount,
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_pending_withdrawal(&self, account: AccountId) -> Balance {
+            self.pending_withdrawals.get(account).unwrap_or(0)
+        }
+
+        /// Requests the exit of `tokens` of the position at the current multiplier, leaving
+        /// the rest riding. Settles with the rest of the position once the game resolves.
+        #[ink(message)]
+        pub fn partial_exit(&mut self, game_id: u64, tokens: Balance) -> Result<(), Error> {
+            let caller = self.env().caller();
+            let game = self.games.get(game_id).ok_or(Error::NoActiveGame)?;
+            let (mut game, mut player) = self.position_in(game, caller)?;
+            if tokens == 0 {
+                return Err(Error::ZeroFunds);
+            }
+            if tokens > player.token_balance {
+                return Err(Error::InsufficientTokens);
+            }
+            let multiplier = self.multiplier_at(&game, self.env().block_number());
+            let price = Self::price_at(&game, multiplier);
+            self.request_exit(&mut game, caller, &mut player, tokens, multiplier, price)
+        }
+
+        /// Requests the exit of each account whose auto-cashout target the live price has
+        /// reached, locked in at the target itself.
+        #[ink(message)]
+        pub fn batch_process_exits(
+            &mut self,
+            track_id: u64,
+            accounts: Vec<AccountId>,
+        ) -> Result<u32, Error> {
+            let caller = self.env().caller();
+            if !self.is_admin(caller) && self.keeper != Some(caller) {
+                return Err(Error::NotKeeper);
+            }
+            if accounts.len() > MAX_BATCH_SIZE {
+                return Err(Error::BatchTooLarge);
+            }
+            let mut game = self.current_game_of(track_id)?;
+            if game.crashed || game.cancelled {
+                return Ok(0);
+            }
+            let multiplier = self.multiplier_at(&game, self.env().block_number());
+            let mut exited = 0;
+            for account in accounts {
+                let Some(mut player) = self.players.get((game.id, account)) else {
+                    continue;
+                };
+                if player.exited {
+                    continue;
+                }
+                let Some(target) = player.auto_cashout_price else {
+                    continue;
+                };
+                let target_multiplier = Self::multiplier_for(&game, target);
+                if target_multiplier > multiplier {
+                    continue;
+                }
+                let tokens = player.token_balance;
+                match self.request_exit(
+                    &mut game,
+                    account,
+                    &mut player,
+                    tokens,
+                    target_multiplier,
+                    target,
+                ) {
+                    Ok(()) => exited += 1,
+                    Err(Error::ExitQueueFull) => {}
+                    Err(error) => return Err(error),
+                }
+            }
+            Ok(exited)
+        }
+
+        #[ink(message)]
+        pub fn cancel_game(&mut self, game_id: u64) -> Result<(), Error> {
+            self.only_admin()?;
+            let mut game = self.games.get(game_id).ok_or(Error::NoActiveGame)?;
+            if game.crashed {
+                return Err(Error::GameCrashed);
+            }
+            if game.cancelled {
+                return Err(Error::GameCancelled);
+            }
+            if game.final_multiplier > 0 {
+                return Err(Error::GameResolved);
+            }
+            self.cancel(&mut game);
+            Ok(())
+        }
+
+        /// Every deposit becomes refundable, so the reserve held for exit requests is
+        /// released in favour of the refund reserve.
+        fn cancel(&mut self, game: &mut Game) {
+            game.cancelled = true;
+            game.state = GameState::Cancelled;
+            let reserved = game.reserved;
+            self.release_claim(game, reserved);
+            self.games.insert(game.id, game);
+            let refundable = game.open_deposits;
+            self.refund_reserve += refundable;
+            self.env().emit_event(GameCancelled {
+                game_id: game.id,
+                refundable,
+            });
+        }
+
+        #[ink(message)]
+        pub fn trigger_liquidation(&mut self) -> Result<(), Error> {
+            if self.liquidated {
+                return Err(Error::Liquidated);
+            }
+            let inactive_for = self
+                .env()
+                .block_number()
+                .saturating_sub(self.owner_last_active);
+            if inactive_for <= self.liquidation_threshold {
+                return Err(Error::OwnerActive);
+            }
+            self.liquidated = true;
+            let games: Vec<Game> = self.current_games().collect();
+            for mut game in games {
+                self.cancel(&mut game);
+            }
+            self.env().emit_event(LiquidationTriggered {
+                by: self.env().caller(),
+                owner_last_active: self.owner_last_active,
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn force_crash(&mut self, track_id: u64) -> Result<(), Error> {
+            self.only_owner()?;
+            let mut game = self.current_game_of(track_id)?;
+            if game.crashed {
+                return Err(Error::GameCrashed);
+            }
+            if game.cancelled {
+                return Err(Error::GameCancelled);
+            }
+            // Once the grace period has begun the crash point is about to be rolled.
+            if game.final_multiplier > 0 || game.crash_block > 0 {
+                return Err(Error::GameResolved);
+            }
+            let price = self.live_price(&game);
+            game.crashed = true;
+            game.state = GameState::Crashed;
+            game.crash_multiplier = self.multiplier_at(&game, self.env().block_number());
+            game.final_multiplier = game.crash_multiplier;
+            game.crash_threshold = game.crash_multiplier;
+            game.resolution_block = self.env().block_number();
+            game.resolver = Some(self.env().caller());
+            game.forfeited = self.value_of(game.open_tokens, price)?;
+            self.games.insert(game.id, &game);
+            self.env().emit_event(GameCrashed {
+                game_id: game.id,
+                crash_multiplier: game.crash_multiplier,
+                final_pool: game.game_pool,
+                forfeited: game.forfeited,
+                forced: true,
+                resolution_entropy: game.resolution_entropy,
+            });
+            Ok(())
+        }
+
+        /// Deletes the caller's record for a finished game. The storage deposit held for
+        /// the entry is released back to the caller, so players are not charged rent
+        /// for positions that can no longer change.
+        #[ink(message)]
+        pub fn clear_position(&mut self, game_id: u64) -> Result<(), Error> {
+            let mut game = self.games.get(game_id).ok_or(Error::NoActiveGame)?;
+            let caller = self.env().caller();
+            let mut player = self
+                .players
+                .get((game_id, caller))
+                .ok_or(Error::NotInGame)?;
+            let claimable = match game.state {
+                GameState::Crashed => !player.pending_exits.is_empty(),
+                GameState::Cashed => !player.exited || !player.pending_exits.is_empty(),
+                GameState::Cancelled => !player.settled,
+                GameState::Open | GameState::Resolving => true,
+            };
+            if claimable {
+                return Err(Error::PositionUnsettled);
+            }
+            if game.crashed && !player.settled {
+                self.settle(&mut game, caller, &mut player)?;
+            }
+            self.players.remove((game_id, caller));
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn sweep_game(&mut self, game_id: u64) -> Result<u32, Error> {
+            self.only_owner()?;
+            let mut game = self.games.get(game_id).ok_or(Error::NoActiveGame)?;
+            if !game.crashed {
+                return Err(Error::GameNotCrashed);
+            }
+            if self
+                .tracks
+                .get(game.track_id)
+                .is_some_and(|track| track.current_game_id == game_id)
+            {
+                return Err(Error::GameActive);
+            }
+            if self.env().block_number() < game.resolution_block.saturating_add(SWEEP_DELAY) {
+                return Err(Error::GameTooRecent);
+            }
+            let (cleared, remaining) = self.clear_participants(&mut game, MAX_BATCH_SIZE as u32)?;
+            self.env().emit_event(GameSwept {
+                game_id,
+                forfeited: game.forfeited,
+                cleared,
+                remaining,
+            });
+            Ok(cleared)
+        }
+
+        /// Settles and deletes up to `limit` positions from the back of the participant
+        /// list, returning how many were cleared and how many remain.
+        fn clear_participants(&mut self, game: &mut Game, limit: u32) -> Result<(u32, u32), Error> {
+            let count = self.participant_counts.get(game.id).unwrap_or(0);
+            let remaining = count.saturating_sub(limit);
+            for index in remaining..count {
+                let Some(account) = self.participants.take((game.id, index)) else {
+                    continue;
+                };
+                if let Some(mut player) = self.players.get((game.id, account)) {
+                    if !player.settled {
+                        self.settle(game, account, &mut player)?;
+                    }
+                }
+                self.players.remove((game.id, account));
+            }
+            if remaining == 0 {
+                self.participant_counts.remove(game.id);
+                self.auto_cashouts.remove(game.id);
+            } else {
+                self.participant_counts.insert(game.id, &remaining);
+            }
+            Ok((count - remaining, remaining))
+        }
+
+        #[ink(message)]
+        pub fn claim_refund(&mut self, game_id: u64) -> Result<(), Error> {
+            let mut game = self.games.get(game_id).ok_or(Error::NoActiveGame)?;
+            if !game.cancelled {
+                return Err(Error::GameNotCancelled);
+            }
+            let caller = self.env().caller();
+            let amount = self.release_stake(&mut game, caller)?;
+            self.refund_reserve -= amount;
+            Ok(())
+        }
+
+        /// The stall is counted from the end of the betting window, when the game was due
+        /// to resolve, not from its start.
+        #[ink(message)]
+        pub fn emergency_withdraw(&mut self, track_id: u64) -> Result<(), Error> {
+            let track = self.tracks.get(track_id).ok_or(Error::TrackNotFound)?;
+            let stalled_for = self
+                .env()
+                .block_number()
+                .saturating_sub(track.last_game_block.saturating_add(track.game_interval));
+            if stalled_for <= self.stall_timeout {
+                return Err(Error::NotStalled);
+            }
+            let mut game = self.current_game_of(track_id)?;
+            if game.crashed {
+                return Err(Error::GameCrashed);
+            }
+            if game.cancelled {
+                return Err(Error::GameCancelled);
+            }
+            let caller = self.env().caller();
+            self.release_stake(&mut game, caller)?;
+            Ok(())
+        }
+
+        /// Refunds what the position actually deposited. Entry prices rise through the
+        /// window and early entries carry bonus tokens, so valuing the tokens at the
+        /// game's base price would not return the stake.
+        fn release_stake(&mut self, game: &mut Game, account: AccountId) -> Result<Balance, Error> {
+            let key = (game.id, account);
+            let mut player = self.players.get(key).ok_or(Error::NotInGame)?;
+            if player.settled {
+                return Err(Error::AlreadyExited);
+            }
+            let amount = player.total_deposited;
+            self.casino_pool = self
+                .casino_pool
+                .checked_sub(amount)
+                .ok_or(Error::InconsistentAccounting)?;
+            let requested = player
+                .pending_exits
+                .drain(..)
+                .fold(0, |total: Balance, exit| total.saturating_add(exit.value));
+            self.release_claim(game, requested);
+            if !player.exited {
+                game.open_tokens -= player.token_balance + player.bonus_tokens;
+                game.active_players -= 1;
+            }
+            game.open_deposits -= amount;
+            player.token_balance = 0;
+            player.bonus_tokens = 0;
+            player.total_deposited = 0;
+            player.exited = true;
+            self.close_position(game, &mut player);
+            self.games.insert(game.id, game);
+            self.players.insert(key, &player);
+            self.pay(account, amount)?;
+            self.env().emit_event(PlayerRefunded {
+                player: account,
+                game_id: game.id,
+                amount,
+            });
+            Ok(amount)
+        }
+
+        #[ink(message)]
+        pub fn set_auto_cashout(&mut self, track_id: u64, target: Balance) -> Result<(), Error> {
+            let game = self.current_game_of(track_id)?;
+            let game_id = game.id;
+            if game.crashed {
+                return Err(Error::GameCrashed);
+            }
+            if game.cancelled {
+                return Err(Error::GameCancelled);
+            }
+            if game.final_multiplier > 0 {
+                return Err(Error::GameResolved);
+            }
+            if target == 0 {
+                return Err(Error::InvalidTarget);
+            }
+            let caller = self.env().caller();
+            let key = (game_id, caller);
+            let mut player = self.players.get(key).ok_or(Error::NotInGame)?;
+            if player.exited {
+                return Err(Error::AlreadyExited);
+            }
+            if player.auto_cashout_price.is_none() {
+                let mut queue = self.auto_cashouts.get(game_id).unwrap_or_default();
+                if queue.len() >= MAX_AUTO_CASHOUTS {
+                    return Err(Error::AutoCashoutQueueFull);
+                }
+                queue.push(caller);
+                self.auto_cashouts.insert(game_id, &queue);
+            }
+            player.auto_cashout_price = Some(target);
+            self.players.insert(key, &player);
+            Ok(())
+        }
+
+        /// Settles a position in a resolved game, crediting whatever it won to the
+        /// account's pending withdrawals. Anyone may call it, so the reserve held for an
+        /// unclaimed position can still be released.
+        #[ink(message)]
+        pub fn settle_position(
+            &mut self,
+            game_id: u64,
+            account: AccountId,
+        ) -> Result<Balance, Error> {
+            let mut game = self.games.get(game_id).ok_or(Error::NoActiveGame)?;
+            if game.cancelled {
+                return Err(Error::GameCancelled);
+            }
+            if game.final_multiplier == 0 {
+                return Err(Error::GameActive);
+            }
+            if game.mode == GameMode::LastStanding {
+                return Err(Error::ExitsDisabled);
+            }
+            let mut player = self
+                .players
+                .get((game_id, account))
+                .ok_or(Error::NotInGame)?;
+            if player.settled {
+                return Err(Error::AlreadyExited);
+            }
+            self.settle(&mut game, account, &mut player)
+        }
+
+        #[ink(message)]
+        pub fn cancel_auto_cashout(&mut self, track_id: u64) -> Result<(), Error> {
+            let caller = self.env().caller();
+            let (game, mut player) = self.open_position(track_id, caller)?;
+            if player.auto_cashout_price.take().is_none() {
+                return Err(Error::InvalidTarget);
+            }
+            let mut queue = self.auto_cashouts.get(game.id).unwrap_or_default();
+            queue.retain(|account| *account != caller);
+            self.auto_cashouts.insert(game.id, &queue);
+            self.players.insert((game.id, caller), &player);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn commit_seed(&mut self, game_id: u64, commitment: Hash) -> Result<(), Error> {
+            self.only_admin()?;
+            if game_id <= self.game_count {
+                return Err(Error::GameAlreadyStarted);
+            }
+            if self.seed_commits.contains(game_id) {
+                return Err(Error::SeedAlreadyCommitted);
+            }
+            self.seed_commits.insert(game_id, &commitment);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn reveal_seed(&mut self, game_id: u64, seed: [u8; 32]) -> Result<(), Error> {
+            let commitment = self
+                .seed_commits
+                .get(game_id)
+                .ok_or(Error::NoSeedCommitted)?;
+            if self.revealed_seeds.contains(game_id) {
+                return Err(Error::SeedAlreadyRevealed);
+            }
+            let mut game = self.games.get(game_id).ok_or(Error::RevealTooEarly)?;
+            let block = self.env().block_number();
+            if block < game.start_block + self.interval_of(&game) {
+                return Err(Error::RevealTooEarly);
+            }
+            let hash = self.env().hash_bytes::<ink::env::hash::Blake2x256>(&seed);
+            if Hash::from(hash) != commitment {
+                return Err(Error::SeedMismatch);
+            }
+            game.reveal_block = block;
+            game.reveal_timestamp = self.env().block_timestamp();
+            self.roll_crash(&mut game, Some(seed));
+            self.games.insert(game_id, &game);
+            self.revealed_seeds.insert(game_id, &seed);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_seed_commitment(&self, game_id: u64) -> Option<Hash> {
+            self.seed_commits.get(game_id)
+        }
+
+        #[ink(message)]
+        pub fn set_treasury(&mut self, treasury: AccountId) -> Result<(), Error> {
+            self.only_owner()?;
+            Self::ensure_valid_address(treasury)?;
+            self.env().emit_event(AddressChanged {
+                field: String::from("treasury"),
+                old_address: Some(self.treasury),
+                new_address: Some(treasury),
+            });
+            self.treasury = treasury;
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn grant_admin(&mut self, account: AccountId) -> Result<(), Error> {
+            self.only_owner()?;
+            Self::ensure_valid_address(account)?;
+            self.admins.insert(account, &true);
+            self.access_changed(account, "admin", true);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn revoke_admin(&mut self, account: AccountId) -> Result<(), Error> {
+            self.only_owner()?;
+            self.admins.remove(account);
+            self.access_changed(account, "admin", false);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn transfer_ownership(&mut self, new_owner: AccountId) -> Result<(), Error> {
+            self.only_owner()?;
+            Self::ensure_valid_address(new_owner)?;
+            self.pending_owner = Some(new_owner);
+            self.env().emit_event(PendingOwnershipTransfer {
+                owner: self.owner,
+                pending_owner: new_owner,
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn accept_ownership(&mut self) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if self.pending_owner != Some(caller) {
+                return Err(Error::NotPendingOwner);
+            }
+            let previous_owner = self.owner;
+            self.owner = caller;
+            self.pending_owner = None;
+            self.owner_last_active = self.env().block_number();
+            self.env().emit_event(OwnershipTransferred {
+                previous_owner,
+                new_owner: caller,
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn pause(&mut self) -> Result<(), Error> {
+            self.only_admin()?;
+            self.paused = true;
+            self.env().emit_event(Paused {
+                by: self.env().caller(),
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn unpause(&mut self) -> Result<(), Error> {
+            self.only_admin()?;
+            self.paused = false;
+            self.env().emit_event(Unpaused {
+                by: self.env().caller(),
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn set_blacklisted(
+            &mut self,
+            account: AccountId,
+            blacklisted: bool,
+        ) -> Result<(), Error> {
+            self.only_admin()?;
+            if blacklisted {
+                self.blacklist.insert(account, &true);
+            } else {
+                self.blacklist.remove(account);
+            }
+            self.access_changed(account, "blacklist", blacklisted);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn set_allowed(&mut self, account: AccountId, allowed: bool) -> Result<(), Error> {
+            self.only_admin()?;
+            if allowed {
+                self.allowlist.insert(account, &true);
+            } else {
+                self.allowlist.remove(account);
+            }
+            self.access_changed(account, "allowlist", allowed);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn set_restricted(&mut self, restricted: bool) -> Result<(), Error> {
+            self.only_admin()?;
+            self.config_changed("restricted", self.restricted.into(), restricted.into());
+            self.restricted = restricted;
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn set_stall_timeout(&mut self, stall_timeout: u32) -> Result<(), Error> {
+            self.only_admin()?;
+            if stall_timeout == 0 {
+                return Err(Error::InvalidInterval);
+            }
+            self.config_changed(
+                "stall_timeout",
+                self.stall_timeout.into(),
+                stall_timeout.into(),
+            );
+            self.stall_timeout = stall_timeout;
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn set_max_grace_blocks(&mut self, max_grace_blocks: u32) -> Result<(), Error> {
+            self.only_admin()?;
+            if max_grace_blocks > MAX_GRACE_BLOCKS {
+                return Err(Error::InvalidInterval);
+            }
+            self.config_changed(
+                "max_grace_blocks",
+                self.max_grace_blocks.into(),
+                max_grace_blocks.into(),
+            );
+            self.max_grace_blocks = max_grace_blocks;
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_max_grace_blocks(&self) -> u32 {
+            self.max_grace_blocks
+        }
+
+        #[ink(message)]
+        pub fn set_entry_delay(&mut self, entry_delay: u32) -> Result<(), Error> {
+            self.only_admin()?;
+            // A delay that outlasts a window would leave that track with no entry block.
+            let shortest = (0..self.track_count)
+                .filter_map(|track_id| self.tracks.get(track_id))
+                .map(|track| track.game_interval)
+                .min();
+            if shortest.is_some_and(|game_interval| entry_delay >= game_interval) {
+                return Err(Error::InvalidInterval);
+            }
+            self.config_changed("entry_delay", self.entry_delay.into(), entry_delay.into());
+            self.entry_delay = entry_delay;
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_entry_delay(&self) -> u32 {
+            self.entry_delay
+        }
+
+        #[ink(message)]
+        pub fn set_bet_cooldown(&mut self, bet_cooldown: u32) -> Result<(), Error> {
+            self.only_admin()?;
+            self.config_changed(
+                "bet_cooldown",
+                self.bet_cooldown.into(),
+                bet_cooldown.into(),
+            );
+            self.bet_cooldown = bet_cooldown;
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn set_liquidation_threshold(
+            &mut self,
+            liquidation_threshold: u32,
+        ) -> Result<(), Error> {
+            self.only_owner()?;
+            if liquidation_threshold == 0 {
+                return Err(Error::InvalidInterval);
+            }
+            self.config_changed(
+                "liquidation_threshold",
+                self.liquidation_threshold.into(),
+                liquidation_threshold.into(),
+            );
+            self.liquidation_threshold = liquidation_threshold;
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn set_keeper(&mut self, keeper: Option<AccountId>) -> Result<(), Error> {
+            self.only_admin()?;
+            self.env().emit_event(AddressChanged {
+                field: String::from("keeper"),
+                old_address: self.keeper,
+                new_address: keeper,
+            });
+            self.keeper = keeper;
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn set_keeper_reward(&mut self, keeper_reward: Balance) -> Result<(), Error> {
+            self.only_admin()?;
+            self.config_changed("keeper_reward", self.keeper_reward, keeper_reward);
+            self.keeper_reward = keeper_reward;
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_keeper_reward(&self) -> Balance {
+            self.keeper_reward
+        }
+
+        #[ink(message)]
+        pub fn set_auto_restart(&mut self, auto_restart: bool) -> Result<(), Error> {
+            self.only_admin()?;
+            self.config_changed(
+                "auto_restart",
+                self.auto_restart.into(),
+                auto_restart.into(),
+            );
+            self.auto_restart = auto_restart;
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_auto_restart(&self) -> bool {
+            self.auto_restart
+        }
+
+        /// Checks the transaction's caller rather than the position's beneficiary, and
+        /// for scheduled entries the account that scheduled them.
+        #[ink(message)]
+        pub fn set_block_contracts(&mut self, block_contracts: bool) -> Result<(), Error> {
+            self.only_admin()?;
+            self.config_changed(
+                "block_contracts",
+                self.block_contracts.into(),
+                block_contracts.into(),
+            );
+            self.block_contracts = block_contracts;
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_block_contracts(&self) -> bool {
+            self.block_contracts
+        }
+
+        #[ink(message)]
+        pub fn start_game(&mut self, track_id: u64) -> Result<u64, Error> {
+            self.only_admin()?;
+            if self.liquidated {
+                return Err(Error::Liquidated);
+            }
+            let mut track = self.tracks.get(track_id).ok_or(Error::TrackNotFound)?;
+            if let Some(game) = self.games.get(track.current_game_id) {
+                let ended = game.crashed || game.cancelled || game.state == GameState::Cashed;
+                if !ended {
+                    return Err(Error::GameActive);
+                }
+            }
+            if !self.start_new_game(&mut track, self.env().block_number()) {
+                return Err(Error::PoolBelowMinimum);
+            }
+            Ok(track.current_game_id)
+        }
+
+        #[ink(message)]
+        pub fn create_game_track(&mut self, game_interval: u32) -> Result<u64, Error> {
+            self.only_admin()?;
+            if self.track_count >= MAX_TRACKS {
+                return Err(Error::TooManyTracks);
+            }
+            if !Self::valid_interval(game_interval) || game_interval <= self.entry_delay {
+                return Err(Error::InvalidInterval);
+            }
+            let track = Track {
+                id: self.track_count,
+                game_interval,
+                last_game_block: self.env().block_number(),
+                current_game_id: 0,
+                mode: GameMode::CashOut,
+            };
+            self.tracks.insert(track.id, &track);
+            self.track_count += 1;
+            self.env().emit_event(GameIntervalChanged {
+                track_id: track.id,
+                old_interval: 0,
+                new_interval: game_interval,
+            });
+            Ok(track.id)
+        }
+
+        #[ink(message)]
+        pub fn set_game_interval(&mut self, track_id: u64, new_interval: u32) -> Result<(), Error> {
+            self.only_admin()?;
+            if !Self::valid_interval(new_interval) || new_interval <= self.entry_delay {
+                return Err(Error::InvalidInterval);
+            }
+            let mut track = self.tracks.get(track_id).ok_or(Error::TrackNotFound)?;
+            self.env().emit_event(GameIntervalChanged {
+                track_id,
+                old_interval: track.game_interval,
+                new_interval,
+            });
+            track.game_interval = new_interval;
+            self.tracks.insert(track_id, &track);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn set_game_mode(&mut self, track_id: u64, mode: GameMode) -> Result<(), Error> {
+            self.only_admin()?;
+            let mut track = self.tracks.get(track_id).ok_or(Error::TrackNotFound)?;
+            self.env().emit_event(GameModeChanged {
+                track_id,
+                old_mode: track.mode,
+                new_mode: mode,
+            });
+            track.mode = mode;
+            self.tracks.insert(track_id, &track);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn set_last_standing_winners(&mut self, winners: u32) -> Result<(), Error> {
+            self.only_admin()?;
+            if winners == 0 || winners > MAX_BATCH_SIZE as u32 {
+                return Err(Error::InvalidWinnerCount);
+            }
+            self.config_changed(
+                "last_standing_winners",
+                self.last_standing_winners.into(),
+                winners.into(),
+            );
+            self.last_standing_winners = winners;
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_last_standing_winners(&self) -> u32 {
+            self.last_standing_winners
+        }
+
+        #[ink(message)]
+        pub fn set_base_price(&mut self, base_price: Balance) -> Result<(), Error> {
+            self.only_admin()?;
+            if base_price == 0 {
+                return Err(Error::InvalidPrice);
+            }
+            self.config_changed("base_price", self.base_price, base_price);
+            self.base_price = base_price;
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_base_price(&self) -> Balance {
+            self.base_price
+        }
+
+        #[ink(message)]
+        pub fn get_price_scale(&self) -> Balance {
+            self.price_scale
+        }
+
+        #[ink(message)]
+        pub fn set_bet_limits(&mut self, min_bet: Balance, max_bet: Balance) -> Result<(), Error> {
+            self.only_admin()?;
+            if max_bet < min_bet {
+                return Err(Error::InvalidBetLimits);
+            }
+            self.config_changed("min_bet", self.min_bet, min_bet);
+            self.min_bet = min_bet;
+            self.config_changed("max_bet", self.max_bet, max_bet);
+            self.max_bet = max_bet;
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_bet_limits(&self) -> (Balance, Balance) {
+            (self.min_bet, self.max_bet)
+        }
+
+        #[ink(message)]
+        pub fn set_house_fee(&mut self, fee_bps: u16) -> Result<(), Error> {
+            self.only_admin()?;
+            if fee_bps > 10_000 {
+                return Err(Error::InvalidFee);
+            }
+            self.config_changed("house_fee_bps", self.house_fee_bps.into(), fee_bps.into());
+            self.house_fee_bps = fee_bps;
+            Ok(())
+        }
+
+        /// Overrides the fee for a single game, taking precedence over the house fee and
+        /// any fee tier. Future games pick the override up when they start.
+        #[ink(message)]
+        pub fn set_game_fee(&mut self, game_id: u64, fee_bps: Option<u16>) -> Result<(), Error> {
+            self.only_admin()?;
+            if fee_bps.is_some_and(|bps| bps > 10_000) {
+                return Err(Error::InvalidFee);
+            }
+            if game_id > self.game_count {
+                match fee_bps {
+                    Some(bps) => {
+                        self.fee_overrides.insert(game_id, &bps);
+                    }
+                    None => self.fee_overrides.remove(game_id),
+                }
+            } else {
+                let mut game = self.games.get(game_id).ok_or(Error::NoActiveGame)?;
+                if game.crashed || game.cancelled || game.state == GameState::Cashed {
+                    return Err(Error::NoActiveGame);
+                }
+                game.fee_override_bps = fee_bps;
+                self.games.insert(game_id, &game);
+            }
+            self.env()
+                .emit_event(GameFeeOverridden { game_id, fee_bps });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn set_fee_tiers(&mut self, tiers: Vec<(Balance, u16)>) -> Result<(), Error> {
+            self.only_admin()?;
+            if tiers.len() > MAX_FEE_TIERS
+                || tiers.iter().any(|(_, bps)| *bps > 10_000)
+                || tiers.windows(2).any(|pair| pair[0].0 >= pair[1].0)
+            {
+                return Err(Error::InvalidFeeTiers);
+            }
+            self.env().emit_event(FeeTiersChanged {
+                old_tiers: self.fee_tiers.clone(),
+                new_tiers: tiers.clone(),
+            });
+            self.fee_tiers = tiers;
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_fee_tiers(&self) -> Vec<(Balance, u16)> {
+            self.fee_tiers.clone()
+        }
+
+        /// Entries in the first `blocks` of a window earn `bonus_bps` extra tokens, held
+        /// apart from the stake and paid only if the position rides to resolution.
+        #[ink(message)]
+        pub fn set_early_bonus(&mut self, blocks: u32, bonus_bps: u16) -> Result<(), Error> {
+            self.only_admin()?;
+            if bonus_bps > 10_000 {
+                return Err(Error::InvalidFee);
+            }
+            self.config_changed(
+                "early_bonus_blocks",
+                self.early_bonus_blocks.into(),
+                blocks.into(),
+            );
+            self.config_changed(
+                "early_bonus_bps",
+                self.early_bonus_bps.into(),
+                bonus_bps.into(),
+            );
+            self.early_bonus_blocks = blocks;
+            self.early_bonus_bps = bonus_bps;
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_early_bonus(&self) -> (u32, u16) {
+            (self.early_bonus_blocks, self.early_bonus_bps)
+        }
+
+        #[ink(message)]
+        pub fn set_holding_bonus(&mut self, bonus_bps: u16) -> Result<(), Error> {
+            self.only_admin()?;
+            if bonus_bps > 10_000 {
+                return Err(Error::InvalidFee);
+            }
+            self.config_changed(
+                "holding_bonus_bps",
+                self.holding_bonus_bps.into(),
+                bonus_bps.into(),
+            );
+            self.holding_bonus_bps = bonus_bps;
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_holding_bonus(&self) -> u16 {
+            self.holding_bonus_bps
+        }
+
+        #[ink(message)]
+        pub fn set_referral_bps(&mut self, referral_bps: u16) -> Result<(), Error> {
+            self.only_admin()?;
+            if referral_bps > 10_000 {
+                return Err(Error::InvalidFee);
+            }
+            self.config_changed(
+                "referral_bps",
+                self.referral_bps.into(),
+                referral_bps.into(),
+            );
+            self.referral_bps = referral_bps;
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn set_max_payout_per_player(&mut self, max_payout: Balance) -> Result<(), Error> {
+            self.only_admin()?;
+            self.config_changed(
+                "max_payout_per_player",
+                self.max_payout_per_player,
+                max_payout,
+            );
+            self.max_payout_per_player = max_payout;
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn set_max_game_pool(&mut self, max_game_pool: Balance) -> Result<(), Error> {
+            self.only_admin()?;
+            self.config_changed("max_game_pool", self.max_game_pool, max_game_pool);
+            self.max_game_pool = max_game_pool;
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_max_game_pool(&self) -> Balance {
+            self.max_game_pool
+        }
+
+        #[ink(message)]
+        pub fn set_max_account_exposure(
+            &mut self,
+            max_account_exposure: Balance,
+        ) -> Result<(), Error> {
+            self.only_admin()?;
+            self.config_changed(
+                "max_account_exposure",
+                self.max_account_exposure,
+                max_account_exposure,
+            );
+            self.max_account_exposure = max_account_exposure;
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_max_account_exposure(&self) -> Balance {
+            self.max_account_exposure
+        }
+
+        #[ink(message)]
+        pub fn set_max_participants(&mut self, max_participants: u32) -> Result<(), Error> {
+            self.only_admin()?;
+            self.config_changed(
+                "max_participants",
+                self.max_participants.into(),
+                max_participants.into(),
+            );
+            self.max_participants = max_participants;
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_max_participants(&self) -> u32 {
+            self.max_participants
+        }
+
+        #[ink(message)]
+        pub fn set_history_limit(&mut self, history_limit: u64) -> Result<(), Error> {
+            self.only_admin()?;
+            self.config_changed(
+                "history_limit",
+                self.history_limit.into(),
+                history_limit.into(),
+            );
+            self.history_limit = history_limit;
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_history_limit(&self) -> u64 {
+            self.history_limit
+        }
+
+        /// Returns the inclusive range of game ids still held in storage.
+        #[ink(message)]
+        pub fn get_retained_window(&self) -> (u64, u64) {
+            (self.oldest_game_id, self.game_count)
+        }
+
+        #[ink(message)]
+        pub fn set_max_multiplier(&mut self, max_multiplier: u32) -> Result<(), Error> {
+            self.only_admin()?;
+            if max_multiplier < MULTIPLIER_BASE {
+                return Err(Error::InvalidMultiplier);
+            }
+            self.config_changed(
+                "max_multiplier",
+                self.max_multiplier.into(),
+                max_multiplier.into(),
+            );
+            self.max_multiplier = max_multiplier;
+            Ok(())
+        }
+
+        /// Share of games, in basis points, that bust at 1.00x. Every other game crashes
+        /// at a point drawn from the curve, so raising it shifts the whole distribution
+        /// down; no setting leaves a game unable to crash.
+        #[ink(message)]
+        pub fn set_crash_probability(&mut self, crash_probability_bps: u16) -> Result<(), Error> {
+            self.only_admin()?;
+            if crash_probability_bps > 10_000 {
+                return Err(Error::InvalidProbability);
+            }
+            self.config_changed(
+                "crash_probability_bps",
+                self.crash_probability_bps.into(),
+                crash_probability_bps.into(),
+            );
+            self.crash_probability_bps = crash_probability_bps;
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn set_jackpot_bps(&mut self, jackpot_bps: u16) -> Result<(), Error> {
+            self.only_admin()?;
+            if jackpot_bps > 10_000 {
+                return Err(Error::InvalidFee);
+            }
+            self.config_changed("jackpot_bps", self.jackpot_bps.into(), jackpot_bps.into());
+            self.jackpot_bps = jackpot_bps;
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn set_jackpot_probability(&mut self, probability_bps: u16) -> Result<(), Error> {
+            self.only_admin()?;
+            if probability_bps > 10_000 {
+                return Err(Error::InvalidProbability);
+            }
+            self.config_changed(
+                "jackpot_probability_bps",
+                self.jackpot_probability_bps.into(),
+                probability_bps.into(),
+            );
+            self.jackpot_probability_bps = probability_bps;
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_jackpot_pool(&self) -> Balance {
+            self.jackpot_pool
+        }
+
+        #[ink(message)]
+        pub fn set_loss_rebate(
+            &mut self,
+            rebate_threshold: u32,
+            rebate_amount: Balance,
+        ) -> Result<(), Error> {
+            self.only_admin()?;
+            self.config_changed(
+                "rebate_threshold",
+                self.rebate_threshold.into(),
+                rebate_threshold.into(),
+            );
+            self.config_changed("rebate_amount", self.rebate_amount, rebate_amount);
+            self.rebate_threshold = rebate_threshold;
+            self.rebate_amount = rebate_amount;
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_loss_rebate(&self) -> (u32, Balance) {
+            (self.rebate_threshold, self.rebate_amount)
+        }
+
+        #[ink(message, payable)]
+        pub fn fund_rebate_pool(&mut self) -> Result<(), Error> {
+            self.only_owner()?;
+            if self.token.is_some() {
+                return Err(Error::NativeBetsDisabled);
+            }
+            self.credit_rebate_pool(self.env().transferred_value())
+        }
+
+        /// Token-mode counterpart of `fund_rebate_pool`.
+        #[ink(message)]
+        pub fn fund_rebate_pool_with_token(&mut self, amount: Balance) -> Result<(), Error> {
+            self.only_owner()?;
+            self.token.ok_or(Error::TokenNotConfigured)?;
+            self.credit_rebate_pool(amount)?;
+            self.collect(self.env().caller(), amount)
+        }
+
+        fn credit_rebate_pool(&mut self, amount: Balance) -> Result<(), Error> {
+            if amount == 0 {
+                return Err(Error::ZeroFunds);
+            }
+            self.rebate_pool += amount;
+            self.env().emit_event(RebatePoolFunded {
+                from: self.env().caller(),
+                amount,
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_rebate_pool(&self) -> Balance {
+            self.rebate_pool
+        }
+
+        #[ink(message)]
+        pub fn get_loss_streak(&self, account: AccountId) -> u32 {
+            self.player_stats
+                .get(account)
+                .map_or(0, |stats| stats.loss_streak)
+        }
+
+        #[ink(message)]
+        pub fn get_crash_probability(&self) -> u16 {
+            self.crash_probability_bps
+        }
+
+        #[ink(message)]
+        pub fn withdraw_house_revenue(&mut self, amount: Balance) -> Result<(), Error> {
+            self.only_owner()?;
+            if amount > self.house_revenue {
+                return Err(Error::InsufficientRevenue);
+            }
+            self.house_revenue -= amount;
+            self.pay(self.treasury, amount)
+        }
+
+        #[ink(message, payable)]
+        pub fn deposit(&mut self) -> Result<(), Error> {
+            self.fund_pool()
+        }
+
+        #[ink(message, payable)]
+        pub fn fund_pool(&mut self) -> Result<(), Error> {
+            self.only_owner()?;
+            if self.token.is_some() {
+                return Err(Error::NativeBetsDisabled);
+            }
+            self.credit_pool(self.env().transferred_value())
+        }
+
+        /// Token-mode counterpart of `fund_pool` and `deposit`. The owner must have
+        /// approved the casino for `amount` first.
+        #[ink(message)]
+        pub fn fund_pool_with_token(&mut self, amount: Balance) -> Result<(), Error> {
+            self.only_owner()?;
+            self.token.ok_or(Error::TokenNotConfigured)?;
+            self.credit_pool(amount)?;
+            self.collect(self.env().caller(), amount)
+        }
+
+        fn credit_pool(&mut self, amount: Balance) -> Result<(), Error> {
+            if amount == 0 {
+                return Err(Error::ZeroFunds);
+            }
+            self.casino_pool += amount;
+            self.owner_seeded += amount;
+            self.env().emit_event(PoolFunded {
+                from: self.env().caller(),
+                amount,
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn withdraw(&mut self, amount: Balance) -> Result<(), Error> {
+            self.only_owner()?;
+            let free = self.get_free_liquidity();
+            if amount > free {
+                return Err(Error::ReserveBreached);
+            }
+            self.casino_pool -= amount;
+            self.pay(self.treasury, amount)
+        }
+
+        #[ink(message)]
+        pub fn get_current_game(&self, track_id: u64) -> Option<Game> {
+            self.current_game_of(track_id).ok().map(Self::published)
+        }
+
+        #[ink(message)]
+        pub fn get_current_multiplier(&self, track_id: u64) -> u32 {
+            match self.current_game_of(track_id) {
+                Ok(game) if !game.crashed && !game.cancelled => {
+                    self.multiplier_at(&game, self.env().block_number())
+                }
+                _ => 0,
+            }
+        }
+
+        /// The live price while a game runs and the settled price once it cashed out.
+        /// Crashed and cancelled games have no price and return `None`.
+        #[ink(message)]
+        pub fn get_effective_price(&self, game_id: u64) -> Option<Balance> {
+            let game = self.games.get(game_id)?;
+            match game.state {
+                GameState::Crashed | GameState::Cancelled => None,
+                GameState::Cashed => Some(Self::price_at(&game, game.final_multiplier)),
+                GameState::Open | GameState::Resolving => Some(self.live_price(&game)),
+            }
+        }
+
+        #[ink(message)]
+        pub fn get_max_multiplier(&self) -> u32 {
+            self.max_multiplier
+        }
+
+        #[ink(message)]
+        pub fn get_track(&self, track_id: u64) -> Option<Track> {
+            self.tracks.get(track_id)
+        }
+
+        #[ink(message)]
+        pub fn get_track_count(&self) -> u64 {
+            self.track_count
+        }
+
+        #[ink(message)]
+        pub fn get_game(&self, game_id: u64) -> Option<Game> {
+            self.games.get(game_id).map(Self::published)
+        }
+
+        /// Withholds the crash point until the game has resolved.
+        fn published(mut game: Game) -> Game {
+            if game.resolution_block == 0 && !game.cancelled {
+                game.crash_threshold = 0;
+                game.resolution_entropy = 0;
+            }
+            game
+        }
+
+        #[ink(message)]
+        pub fn get_game_state(&self, game_id: u64) -> Option<GameState> {
+            self.games.get(game_id).map(|game| {
+                let closed =
+                    self.env().block_number() >= game.start_block + self.interval_of(&game);
+                if game.state == GameState::Open && closed {
+                    GameState::Resolving
+                } else {
+                    game.state
+                }
+            })
+        }
+
+        #[ink(message)]
+        pub fn verify_game(&self, game_id: u64) -> Option<(FairnessProof, bool)> {
+            let game = self.games.get(game_id)?;
+            if game.resolution_block == 0 {
+                return None;
+            }
+            let seed = self.revealed_seeds.get(game_id);
+            let proof = FairnessProof {
+                game_id,
+                resolution_block: game.resolution_block,
+                resolver: game.resolver,
+                commitment: self.seed_commits.get(game_id),
+                seed,
+                salt: Self::resolution_salt(&game, seed),
+                resolution_entropy: game.resolution_entropy,
+            };
+            Some((proof, game.crashed))
+        }
+
+        #[ink(message)]
+        pub fn get_total_games(&self) -> u64 {
+            self.game_count
+        }
+
+        #[ink(message)]
+        pub fn get_recent_games(&self, start_id: u64, limit: u8) -> Vec<Game> {
+            let start_id = start_id.min(self.game_count);
+            let end_id = start_id.saturating_sub(limit.min(MAX_PAGE_SIZE) as u64);
+            (end_id + 1..=start_id)
+                .rev()
+                .filter_map(|id| self.games.get(id))
+                .map(Self::published)
+                .collect()
+        }
+
+        #[ink(message)]
+        pub fn get_game_summaries(&self, start_id: u64, limit: u8) -> Vec<GameSummary> {
+            let end_id = start_id
+                .saturating_add(limit.min(MAX_PAGE_SIZE) as u64)
+                .min(self.game_count.saturating_add(1));
+            (start_id..end_id)
+                .filter_map(|id| self.games.get(id))
+                .map(|game| GameSummary {
+                    id: game.id,
+                    crashed: game.crashed,
+                    game_pool: game.game_pool,
+                })
+                .collect()
+        }
+
+        #[ink(message)]
+        pub fn get_my_positions(&self, start_id: u64, limit: u8) -> Vec<(u64, Player)> {
+            let caller = self.env().caller();
+            let end_id = start_id
+                .saturating_add(limit.min(MAX_PAGE_SIZE) as u64)
+                .min(self.game_count.saturating_add(1));
+            (start_id..end_id)
+                .filter_map(|id| self.players.get((id, caller)).map(|player| (id, player)))
+                .filter(|(_, player)| !player.exited || !player.pending_exits.is_empty())
+                .collect()
+        }
+
+        #[ink(message)]
+        pub fn get_participant_count(&self, game_id: u64) -> u32 {
+            self.participant_counts.get(game_id).unwrap_or(0)
+        }
+
+        #[ink(message)]
+        pub fn get_participant(&self, game_id: u64, index: u32) -> Option<AccountId> {
+            self.participants.get((game_id, index))
+        }
+
+        #[ink(message)]
+        pub fn get_game_status(&self, game_id: u64) -> Vec<(AccountId, Balance, bool)> {
+            let count = self
+                .participant_counts
+                .get(game_id)
+                .unwrap_or(0)
+                .min(MAX_PAGE_SIZE as u32);
+            (0..count)
+                .filter_map(|index| self.participants.get((game_id, index)))
+                .filter_map(|account| {
+                    self.players
+                        .get((game_id, account))
+                        .map(|player| (account, player.token_balance, player.exited))
+                })
+                .collect()
+        }
+
+        #[ink(message)]
+        pub fn get_my_status(&self, track_id: u64) -> Option<Player> {
+            let game = self.current_game_of(track_id).ok()?;
+            self.players.get((game.id, self.env().caller()))
+        }
+
+        #[ink(message)]
+        pub fn get_my_value(&self, track_id: u64) -> Option<Balance> {
+            let (game, player) = self.open_position(track_id, self.env().caller()).ok()?;
+            self.value_of(player.token_balance, self.live_price(&game))
+                .ok()
+        }
+
+        #[ink(message)]
+        pub fn get_owner(&self) -> AccountId {
+            self.owner
+        }
+
+        #[ink(message)]
+        pub fn get_pending_owner(&self) -> Option<AccountId> {
+            self.pending_owner
+        }
+
+        #[ink(message)]
+        pub fn is_admin(&self, account: AccountId) -> bool {
+            account == self.owner || self.admins.get(account).unwrap_or(false)
+        }
+
+        #[ink(message)]
+        pub fn get_treasury(&self) -> AccountId {
+            self.treasury
+        }
+
+        #[ink(message)]
+        pub fn get_token(&self) -> Option<AccountId> {
+            self.token
+        }
+
+        #[ink(message)]
+        pub fn get_stall_timeout(&self) -> u32 {
+            self.stall_timeout
+        }
+
+        #[ink(message)]
+        pub fn get_bet_cooldown(&self) -> u32 {
+            self.bet_cooldown
+        }
+
+        #[ink(message)]
+        pub fn get_liquidation_threshold(&self) -> u32 {
+            self.liquidation_threshold
+        }
+
+        #[ink(message)]
+        pub fn get_owner_last_active(&self) -> u32 {
+            self.owner_last_active
+        }
+
+        #[ink(message)]
+        pub fn is_liquidated(&self) -> bool {
+            self.liquidated
+        }
+
+        #[ink(message)]
+        pub fn get_keeper(&self) -> Option<AccountId> {
+            self.keeper
+        }
+
+        #[ink(message)]
+        pub fn is_paused(&self) -> bool {
+            self.paused
+        }
+
+        #[ink(message)]
+        pub fn is_blacklisted(&self, account: AccountId) -> bool {
+            self.blacklist.contains(account)
+        }
+
+        #[ink(message)]
+        pub fn is_allowed(&self, account: AccountId) -> bool {
+            self.allowlist.contains(account)
+        }
+
+        #[ink(message)]
+        pub fn is_restricted(&self) -> bool {
+            self.restricted
+        }
+
+        #[ink(message)]
+        pub fn get_referrer(&self, account: AccountId) -> Option<AccountId> {
+            self.referrers.get(account)
+        }
+
+        #[ink(message)]
+        pub fn get_referral_balance(&self, account: AccountId) -> Balance {
+            self.referral_balances.get(account).unwrap_or(0)
+        }
+
+        #[ink(message)]
+        pub fn get_referral_bps(&self) -> u16 {
+            self.referral_bps
+        }
+
+        #[ink(message)]
+        pub fn get_player_stats(&self, account: AccountId) -> Option<PlayerStats> {
+            self.player_stats.get(account)
+        }
+
+        #[ink(message)]
+        pub fn get_leaderboard(&self) -> Vec<(AccountId, Balance)> {
+            self.leaderboard.clone()
+        }
+
+        #[ink(message)]
+        pub fn get_casino_stats(&self) -> CasinoStats {
+            let active_players = self.current_games().map(|game| game.active_players).sum();
+            CasinoStats {
+                total_games: self.game_count,
+                casino_pool: self.casino_pool,
+                house_revenue: self.house_revenue,
+                total_volume: self.total_volume,
+                active_players,
+            }
+        }
+
+        #[ink(message)]
+        pub fn check_solvency(&self) -> (Balance, Balance, bool) {
+            let mut liability = self.refund_reserve.saturating_add(self.claim_reserve);
+            for game in self.current_games() {
+                let price = self.live_price(&game);
+                let count = self
+                    .participant_counts
+                    .get(game.id)
+                    .unwrap_or(0)
+                    .min(MAX_SOLVENCY_SCAN);
+                for index in 0..count {
+                    let Some(account) = self.participants.get((game.id, index)) else {
+                        continue;
+                    };
+                    let Some(player) = self.players.get((game.id, account)) else {
+                        continue;
+                    };
+                    if player.exited {
+                        continue;
+                    }
+                    let value = self
+                        .value_of(player.token_balance + player.bonus_tokens, price)
+                        .unwrap_or(Balance::MAX);
+                    liability = liability.saturating_add(self.capped_payout(&player, value));
+                }
+            }
+            (self.casino_pool, liability, self.casino_pool >= liability)
+        }
+
+        /// Pool coverage of live liabilities in basis points, `u32::MAX` when nothing is owed.
+        #[ink(message)]
+        pub fn get_free_liquidity(&self) -> Balance {
+            self.casino_pool
+                .saturating_sub(self.reserved_liability())
+                .saturating_sub(self.rounding_buffer)
+        }
+
+        #[ink(message)]
+        pub fn get_reserve_ratio(&self) -> u32 {
+            let liability = self.reserved_liability();
+            if liability == 0 {
+                return u32::MAX;
+            }
+            (self.casino_pool.saturating_mul(10_000) / liability).min(u32::MAX as Balance) as u32
+        }
+
+        #[ink(message)]
+        pub fn get_casino_pool(&self) -> Balance {
+            self.casino_pool
+        }
+
+        #[ink(message)]
+        pub fn get_total_paid_out(&self) -> Balance {
+            self.total_paid_out
+        }
+
+        #[ink(message)]
+        pub fn get_rounding_dust(&self) -> Balance {
+            self.rounding_dust
+        }
+
+        #[ink(message)]
+        pub fn set_rounding_buffer(&mut self, rounding_buffer: Balance) -> Result<(), Error> {
+            self.only_admin()?;
+            self.config_changed("rounding_buffer", self.rounding_buffer, rounding_buffer);
+            self.rounding_buffer = rounding_buffer;
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_rounding_buffer(&self) -> Balance {
+            self.rounding_buffer
+        }
+
+        /// Settlement-asset units paid per unit of net payout, scaled by
+        /// `CONVERSION_RATE_BASE`; the default pays one for one.
+        #[ink(message)]
+        pub fn set_conversion_rate(&mut self, conversion_rate: Balance) -> Result<(), Error> {
+            self.only_admin()?;
+            if conversion_rate == 0 {
+                return Err(Error::InvalidConversionRate);
+            }
+            self.config_changed("conversion_rate", self.conversion_rate, conversion_rate);
+            self.conversion_rate = conversion_rate;
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_conversion_rate(&self) -> Balance {
+            self.conversion_rate
+        }
+
+        #[ink(message)]
+        pub fn set_min_pool_to_start(&mut self, min_pool_to_start: Balance) -> Result<(), Error> {
+            self.only_admin()?;
+            self.config_changed(
+                "min_pool_to_start",
+                self.min_pool_to_start,
+                min_pool_to_start,
+            );
+            self.min_pool_to_start = min_pool_to_start;
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_min_pool_to_start(&self) -> Balance {
+            self.min_pool_to_start
+        }
+
+        #[ink(message)]
+        pub fn get_net_house_position(&self) -> i128 {
+            let signed = |value: Balance| i128::try_from(value).unwrap_or(i128::MAX);
+            signed(self.total_volume.saturating_add(self.owner_seeded))
+                .saturating_sub(signed(self.total_paid_out))
+        }
+
+        #[ink(message)]
+        pub fn get_owner_seeded(&self) -> Balance {
+            self.owner_seeded
+        }
+
+        #[ink(message)]
+        pub fn get_house_fee(&self) -> u16 {
+            self.house_fee_bps
+        }
+
+        #[ink(message)]
+        pub fn get_max_payout_per_player(&self) -> Balance {
+            self.max_payout_per_player
+        }
+
+        #[ink(message)]
+        pub fn get_house_revenue(&self) -> Balance {
+            self.house_revenue
+        }
+
+        #[ink(message)]
+        pub fn get_game_interval(&self, track_id: u64) -> Option<u32> {
+            self.tracks.get(track_id).map(|track| track.game_interval)
+        }
+
+        #[ink(message)]
+        pub fn blocks_until_next_game(&self, track_id: u64) -> u32 {
+            self.tracks.get(track_id).map_or(0, |track| {
+                (track.last_game_block + track.game_interval)
+                    .saturating_sub(self.env().block_number())
+            })
+        }
+
+        #[ink(message)]
+        pub fn is_entry_open(&self, track_id: u64) -> bool {
+            if self.paused || self.liquidated {
+                return false;
+            }
+            let block = self.env().block_number();
+            self.current_game_of(track_id).is_ok_and(|game| {
+                !game.crashed
+                    && !game.cancelled
+                    && block < game.start_block + self.interval_of(&game)
+                    && (self.entry_delay == 0 || block > game.start_block + self.entry_delay)
+            })
+        }
+
+        #[ink(message)]
+        pub fn get_version(&self) -> u32 {
+            CONTRACT_VERSION
+        }
+
+        #[ink(message)]
+        pub fn get_schema_version(&self) -> u32 {
+            self.schema_version
+        }
+
+        #[ink(message)]
+        pub fn get_block(&self) -> u32 {
             self.env().block_number()
         }
     }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use core::ops::RangeInclusive;
+        use ink::env::{test, DefaultEnvironment};
+
+        const INTERVAL: u32 = 10;
+        const SCALE: Balance = 1_000_000;
+
+        fn accounts() -> test::DefaultAccounts<DefaultEnvironment> {
+            test::default_accounts::<DefaultEnvironment>()
+        }
+
+        fn set_caller(account: AccountId) {
+            test::set_caller::<DefaultEnvironment>(account);
+        }
+
+        fn set_value(amount: Balance) {
+            test::set_value_transferred::<DefaultEnvironment>(amount);
+        }
+
+        fn advance(blocks: u32) {
+            for _ in 0..blocks {
+                test::advance_block::<DefaultEnvironment>();
+            }
+        }
+
+        fn block() -> u32 {
+            ink::env::block_number::<DefaultEnvironment>()
+        }
+
+        fn balance(account: AccountId) -> Balance {
+            test::get_account_balance::<DefaultEnvironment>(account).unwrap()
+        }
+
+        /// A casino owned by Alice, with `pool` in the casino pool and game 1 open.
+        fn open_casino(pool: Balance) -> CrashCasino {
+            open_casino_priced(pool, SCALE, SCALE)
+        }
+
+        fn open_casino_priced(pool: Balance, base_price: Balance, scale: Balance) -> CrashCasino {
+            set_caller(accounts().alice);
+            test::set_account_balance::<DefaultEnvironment>(
+                test::callee::<DefaultEnvironment>(),
+                1_000_000_000_000,
+            );
+            let mut casino = CrashCasino::new(INTERVAL, None, base_price, scale).unwrap();
+            casino.casino_pool = pool;
+            advance(INTERVAL);
+            casino.tick().unwrap();
+            casino
+        }
+
+        fn enter(
+            casino: &mut CrashCasino,
+            account: AccountId,
+            amount: Balance,
+        ) -> Result<(), Error> {
+            set_caller(account);
+            set_value(amount);
+            let result = casino.enter_game(0);
+            set_value(0);
+            result
+        }
+
+        /// Reveals a seed for `game_id` whose crash point falls in `range`, as if it had
+        /// been revealed in the current block, so the test decides the outcome without
+        /// waiting for the window to close.
+        fn rig(casino: &mut CrashCasino, game_id: u64, range: RangeInclusive<u32>) {
+            let mut game = casino.games.get(game_id).unwrap();
+            game.reveal_block = block();
+            game.reveal_timestamp = ink::env::block_timestamp::<DefaultEnvironment>();
+            for candidate in 0u32.. {
+                let mut seed = [0u8; 32];
+                seed[..4].copy_from_slice(&candidate.to_be_bytes());
+                let mut probe = game.clone();
+                casino.roll_crash(&mut probe, Some(seed));
+                if range.contains(&probe.crash_threshold) {
+                    let mut hash = [0u8; 32];
+                    ink::env::hash_bytes::<ink::env::hash::Blake2x256>(&seed, &mut hash);
+                    casino.seed_commits.insert(game_id, &Hash::from(hash));
+                    casino.revealed_seeds.insert(game_id, &seed);
+                    casino.games.insert(game_id, &probe);
+                    return;
+                }
+            }
+        }
+
+        /// Moves past the current window and resolves it, starting the next game.
+        fn resolve(casino: &mut CrashCasino) {
+            let track = casino.tracks.get(0).unwrap();
+            let due = track.last_game_block + track.game_interval;
+            advance(due.saturating_sub(block()));
+            set_caller(accounts().alice);
+            casino.tick().unwrap();
+        }
+
+        #[ink::test]
+        fn exits_return_the_stake_less_the_house_edge_on_average() {
+            let casino = open_casino(0);
+            let samples = 100_000u64;
+            for multiplier in [110, 150, 200, 500] {
+                let wins = (0..samples)
+                    .map(|index| casino.pseudo_random(&index.to_be_bytes()))
+                    .filter(|&entropy| multiplier < CrashCasino::crash_point(entropy))
+                    .count() as u64;
+                let mean = wins * multiplier as u64 * 1_000 / samples;
+                assert!(
+                    (97_000..=101_000).contains(&mean),
+                    "exit at {multiplier} returned {mean}/100_000 of the stake"
+                );
+            }
+        }
+
+        #[ink::test]
+        fn exits_below_the_crash_point_win_and_exits_above_it_lose() {
+            let mut casino = open_casino(10_000);
+            let accounts = accounts();
+            enter(&mut casino, accounts.bob, 1_000).unwrap();
+            enter(&mut casino, accounts.charlie, 1_000).unwrap();
+            rig(&mut casino, 1, 150..=160);
+
+            advance(2);
+            set_caller(accounts.bob);
+            casino.exit_game(1).unwrap();
+            advance(6);
+            set_caller(accounts.charlie);
+            casino.exit_game(1).unwrap();
+            assert_eq!(casino.claim_reserve, 3_000);
+
+            resolve(&mut casino);
+            let game = casino.games.get(1).unwrap();
+            assert!(game.crashed);
+            assert!((150..=160).contains(&game.crash_multiplier));
+
+            assert_eq!(casino.settle_position(1, accounts.bob), Ok(1_200));
+            assert_eq!(casino.get_pending_withdrawal(accounts.bob), 1_200);
+            assert_eq!(casino.settle_position(1, accounts.charlie), Ok(0));
+            assert_eq!(casino.claim_reserve, 0);
+        }
+
+        #[ink::test]
+        fn auto_cashout_pays_the_target_when_reached_before_the_crash() {
+            let mut casino = open_casino(10_000);
+            let bob = accounts().bob;
+            enter(&mut casino, bob, 1_000).unwrap();
+            casino.set_auto_cashout(0, SCALE * 120 / 100).unwrap();
+            rig(&mut casino, 1, 150..=160);
+
+            resolve(&mut casino);
+            let player = casino.players.get((1, bob)).unwrap();
+            assert!(player.exited);
+            assert_eq!(casino.get_pending_withdrawal(bob), 1_200);
+        }
+
+        #[ink::test]
+        fn auto_cashout_is_lost_when_the_crash_comes_before_the_target() {
+            let mut casino = open_casino(10_000);
+            let bob = accounts().bob;
+            enter(&mut casino, bob, 1_000).unwrap();
+            casino.set_auto_cashout(0, SCALE * 180 / 100).unwrap();
+            rig(&mut casino, 1, 150..=160);
+
+            resolve(&mut casino);
+            assert!(casino.get_game(1).unwrap().crashed);
+            let player = casino.players.get((1, bob)).unwrap();
+            assert!(!player.exited);
+            assert_eq!(casino.settle_position(1, bob), Ok(0));
+        }
+
+        #[ink::test]
+        fn a_committed_seed_is_mixed_with_the_block_it_is_revealed_in() {
+            let mut casino = open_casino(10_000);
+            let seed = [7u8; 32];
+            let mut hash = [0u8; 32];
+            ink::env::hash_bytes::<ink::env::hash::Blake2x256>(&seed, &mut hash);
+            casino.commit_seed(2, Hash::from(hash)).unwrap();
+            resolve(&mut casino);
+            assert_eq!(casino.games.get(2).unwrap().crash_threshold, 0);
+            advance(INTERVAL - 1);
+            assert_eq!(casino.reveal_seed(2, seed), Err(Error::RevealTooEarly));
+
+            advance(8);
+            assert_eq!(casino.tick(), Ok(()));
+            assert_eq!(casino.get_current_game(0).unwrap().id, 2);
+            let mut expected = casino.games.get(2).unwrap();
+            let mut at_window_end = expected.clone();
+            at_window_end.reveal_block = expected.start_block + INTERVAL;
+            expected.reveal_block = block();
+            expected.reveal_timestamp = ink::env::block_timestamp::<DefaultEnvironment>();
+            casino.roll_crash(&mut expected, Some(seed));
+            casino.roll_crash(&mut at_window_end, Some(seed));
+            assert_ne!(
+                expected.resolution_entropy,
+                at_window_end.resolution_entropy
+            );
+
+            casino.reveal_seed(2, seed).unwrap();
+            assert_eq!(casino.reveal_seed(2, seed), Err(Error::SeedAlreadyRevealed));
+            casino.tick().unwrap();
+            let game = casino.get_game(2).unwrap();
+            assert_eq!(game.crash_threshold, expected.crash_threshold);
+            assert_eq!(game.resolution_entropy, expected.resolution_entropy);
+            let salt = CrashCasino::resolution_salt(&game, Some(seed));
+            let jackpot = casino.pseudo_random(&[salt.as_slice(), b"jackpot"].concat());
+            assert_ne!(jackpot, game.resolution_entropy);
+        }
+
+        #[ink::test]
+        fn a_reentrant_withdrawal_finds_the_winnings_already_taken() {
+            let mut casino = open_casino(10_000);
+            let bob = accounts().bob;
+            enter(&mut casino, bob, 1_000).unwrap();
+            rig(&mut casino, 1, 500..=u32::MAX);
+            resolve(&mut casino);
+            let owed = casino.settle_position(1, bob).unwrap();
+            assert!(owed > 0);
+            assert_eq!(casino.get_pending_withdrawal(bob), owed);
+
+            set_caller(bob);
+            let before = balance(bob);
+            casino.withdraw_winnings().unwrap();
+            assert_eq!(balance(bob) - before, owed);
+            // Everything a reentrant call could reach is settled before the transfer.
+            assert_eq!(casino.withdraw_winnings(), Err(Error::NoWinnings));
+            assert_eq!(casino.exit_game(1), Err(Error::AlreadyExited));
+            assert_eq!(casino.settle_position(1, bob), Err(Error::AlreadyExited));
+        }
+
+        #[ink::test]
+        fn cancellation_refunds_what_each_position_deposited() {
+            let mut casino = open_casino(10_000);
+            let accounts = accounts();
+            enter(&mut casino, accounts.bob, 1_000).unwrap();
+            advance(5);
+            enter(&mut casino, accounts.charlie, 1_500).unwrap();
+            advance(1);
+            set_caller(accounts.charlie);
+            casino.exit_game(1).unwrap();
+            set_caller(accounts.alice);
+            casino.cancel_game(1).unwrap();
+            assert_eq!(casino.refund_reserve, 2_500);
+            assert_eq!(casino.claim_reserve, 0);
+
+            for (account, deposit) in [(accounts.bob, 1_000), (accounts.charlie, 1_500)] {
+                let before = balance(account);
+                set_caller(account);
+                casino.claim_refund(1).unwrap();
+                assert_eq!(balance(account) - before, deposit);
+            }
+            assert_eq!(casino.refund_reserve, 0);
+            assert_eq!(casino.claim_refund(1), Err(Error::AlreadyExited));
+        }
+
+        #[ink::test]
+        fn entries_close_at_the_end_of_the_window() {
+            let mut casino = open_casino(10_000);
+            let accounts = accounts();
+            let start = casino.get_game(1).unwrap().start_block;
+            advance(start + INTERVAL - 1 - block());
+            assert_eq!(enter(&mut casino, accounts.bob, 1_000), Ok(()));
+            advance(1);
+            assert_eq!(block(), start + INTERVAL);
+            assert_eq!(
+                enter(&mut casino, accounts.charlie, 1_000),
+                Err(Error::GameExpired)
+            );
+        }
+
+        #[ink::test]
+        fn pseudo_random_reads_four_bytes_of_the_hash() {
+            let casino = open_casino(0);
+            let mut wide = false;
+            for salt in 0u32..16 {
+                let salt = salt.to_be_bytes();
+                let mut hash = [0u8; 32];
+                ink::env::hash_bytes::<ink::env::hash::Blake2x256>(&salt, &mut hash);
+                let value = casino.pseudo_random(&salt);
+                assert_eq!(
+                    value,
+                    u32::from_be_bytes([hash[0], hash[1], hash[2], hash[3]])
+                );
+                wide |= value > u8::MAX as u32;
+            }
+            assert!(wide);
+        }
+
+        #[ink::test]
+        fn anyone_may_tick_without_a_keeper() {
+            let mut casino = open_casino(10_000);
+            set_caller(accounts().django);
+            // Ticks inside the window leave the game alone.
+            casino.tick().unwrap();
+            assert_eq!(casino.get_total_games(), 1);
+            advance(INTERVAL);
+            casino.tick().unwrap();
+            assert_eq!(casino.get_total_games(), 2);
+        }
+
+        #[ink::test]
+        fn only_the_keeper_may_tick_once_one_is_set() {
+            let mut casino = open_casino(10_000);
+            let accounts = accounts();
+            casino.set_keeper(Some(accounts.eve)).unwrap();
+            advance(INTERVAL);
+            set_caller(accounts.django);
+            assert_eq!(casino.tick(), Err(Error::NotKeeper));
+            assert_eq!(casino.tick_track(0), Err(Error::NotKeeper));
+            set_caller(accounts.eve);
+            casino.tick().unwrap();
+            assert_eq!(casino.get_total_games(), 2);
+        }
+
+        #[ink::test]
+        fn partial_exits_take_half_then_the_rest() {
+            let mut casino = open_casino(10_000);
+            let bob = accounts().bob;
+            enter(&mut casino, bob, 1_000).unwrap();
+            rig(&mut casino, 1, 500..=u32::MAX);
+            set_caller(bob);
+            assert_eq!(
+                casino.partial_exit(1, 1_001),
+                Err(Error::InsufficientTokens)
+            );
+
+            advance(2);
+            casino.partial_exit(1, 500).unwrap();
+            let player = casino.players.get((1, bob)).unwrap();
+            assert_eq!(player.token_balance, 500);
+            assert!(!player.exited);
+
+            advance(2);
+            casino.partial_exit(1, 500).unwrap();
+            let player = casino.players.get((1, bob)).unwrap();
+            assert_eq!(player.token_balance, 0);
+            assert!(player.exited);
+            assert_eq!(casino.partial_exit(1, 1), Err(Error::AlreadyExited));
+
+            resolve(&mut casino);
+            assert_eq!(casino.settle_position(1, bob), Ok(600 + 700));
+            assert_eq!(casino.get_pending_withdrawal(bob), 600 + 700);
+        }
+
+        #[ink::test]
+        fn re_entries_add_to_the_deposit_behind_a_position() {
+            let mut casino = open_casino(10_000);
+            let bob = accounts().bob;
+            enter(&mut casino, bob, 1_000).unwrap();
+            advance(2);
+            enter(&mut casino, bob, 1_000).unwrap();
+
+            set_caller(bob);
+            let player = casino.get_my_status(0).unwrap();
+            assert_eq!(player.total_deposited, 2_000);
+            assert!(player.token_balance < 2_000);
+        }
+
+        #[ink::test]
+        fn token_math_reports_overflow_instead_of_wrapping() {
+            let mut casino = open_casino(10_000);
+            assert_eq!(casino.tokens_for(Balance::MAX, SCALE), Err(Error::Overflow));
+            assert_eq!(
+                casino.value_of(Balance::MAX, SCALE * 2),
+                Err(Error::Overflow)
+            );
+            assert_eq!(
+                casino.value_of(Balance::MAX / SCALE, SCALE),
+                Ok(Balance::MAX / SCALE)
+            );
+            assert_eq!(
+                enter(&mut casino, accounts().bob, Balance::MAX / 2),
+                Err(Error::Overflow)
+            );
+        }
+
+        #[ink::test]
+        fn referrers_earn_a_share_of_the_stakes_their_referrals_lose() {
+            let mut casino = open_casino(10_000);
+            casino.set_referral_bps(1_000).unwrap();
+            let accounts = accounts();
+            set_caller(accounts.bob);
+            assert_eq!(
+                casino.enter_game_with_referrer(0, accounts.bob),
+                Err(Error::SelfReferral)
+            );
+            set_value(1_000);
+            casino.enter_game_with_referrer(0, accounts.eve).unwrap();
+            set_caller(accounts.charlie);
+            casino.enter_game_with_referrer(0, accounts.eve).unwrap();
+            set_value(0);
+            casino.exit_game(1).unwrap();
+            assert_eq!(casino.get_referrer(accounts.bob), Some(accounts.eve));
+            rig(&mut casino, 1, 150..=190);
+            resolve(&mut casino);
+
+            // Charlie's exit won, so eve earns nothing from it; bob rode into the crash.
+            casino.settle_position(1, accounts.charlie).unwrap();
+            assert_eq!(casino.get_referral_balance(accounts.eve), 0);
+            let pool = casino.get_casino_pool();
+            casino.settle_position(1, accounts.bob).unwrap();
+            assert_eq!(casino.get_referral_balance(accounts.eve), 100);
+            assert_eq!(casino.get_casino_pool(), pool - 100);
+
+            set_caller(accounts.eve);
+            casino.claim_referral_rewards().unwrap();
+            assert_eq!(casino.get_referral_balance(accounts.eve), 0);
+        }
+
+        #[ink::test]
+        fn token_entries_may_name_a_referrer() {
+            let accounts = accounts();
+            set_caller(accounts.alice);
+            let mut native = CrashCasino::new(INTERVAL, None, SCALE, SCALE).unwrap();
+            set_caller(accounts.bob);
+            assert_eq!(
+                native.enter_game_with_token_and_referrer(0, accounts.eve, 1_000),
+                Err(Error::TokenNotConfigured)
+            );
+            assert_eq!(
+                native.enter_game_with_token_and_referrer(0, accounts.bob, 1_000),
+                Err(Error::SelfReferral)
+            );
+        }
+
+        #[ink::test]
+        fn the_stall_is_counted_from_the_end_of_the_window() {
+            let mut casino = open_casino(10_000);
+            assert_eq!(casino.set_stall_timeout(0), Err(Error::InvalidInterval));
+            casino.set_stall_timeout(5).unwrap();
+            let bob = accounts().bob;
+            enter(&mut casino, bob, 1_000).unwrap();
+            advance(INTERVAL + 5);
+            set_caller(bob);
+            assert_eq!(casino.emergency_withdraw(0), Err(Error::NotStalled));
+            advance(1);
+            let before = balance(bob);
+            casino.emergency_withdraw(0).unwrap();
+            assert_eq!(balance(bob) - before, 1_000);
+        }
+
+        #[ink::test]
+        fn token_casinos_are_funded_through_the_token() {
+            let accounts = accounts();
+            set_caller(accounts.alice);
+            let mut native = CrashCasino::new(INTERVAL, None, SCALE, SCALE).unwrap();
+            assert_eq!(
+                native.fund_pool_with_token(1_000),
+                Err(Error::TokenNotConfigured)
+            );
+            assert_eq!(
+                native.fund_rebate_pool_with_token(1_000),
+                Err(Error::TokenNotConfigured)
+            );
+
+            let mut casino =
+                CrashCasino::new(INTERVAL, Some(accounts.django), SCALE, SCALE).unwrap();
+            set_value(1_000);
+            assert_eq!(casino.fund_pool(), Err(Error::NativeBetsDisabled));
+            assert_eq!(casino.deposit(), Err(Error::NativeBetsDisabled));
+            assert_eq!(casino.fund_rebate_pool(), Err(Error::NativeBetsDisabled));
+            set_value(0);
+            assert_eq!(casino.fund_pool_with_token(0), Err(Error::ZeroFunds));
+            assert_eq!(casino.fund_rebate_pool_with_token(0), Err(Error::ZeroFunds));
+            set_caller(accounts.bob);
+            assert_eq!(casino.fund_pool_with_token(1_000), Err(Error::NotOwner));
+            assert_eq!(
+                casino.fund_rebate_pool_with_token(1_000),
+                Err(Error::NotOwner)
+            );
+        }
+
+        #[ink::test]
+        fn batch_exits_skip_ineligible_accounts() {
+            let mut casino = open_casino(10_000);
+            let accounts = accounts();
+            for account in [
+                accounts.bob,
+                accounts.charlie,
+                accounts.django,
+                accounts.frank,
+            ] {
+                enter(&mut casino, account, 1_000).unwrap();
+            }
+            set_caller(accounts.bob);
+            casino.set_auto_cashout(0, SCALE * 120 / 100).unwrap();
+            set_caller(accounts.charlie);
+            casino.set_auto_cashout(0, SCALE * 2).unwrap();
+            set_caller(accounts.frank);
+            casino.set_auto_cashout(0, SCALE * 110 / 100).unwrap();
+            casino.exit_game(1).unwrap();
+
+            advance(3);
+            let batch = vec![
+                accounts.bob,
+                accounts.charlie,
+                accounts.django,
+                accounts.eve,
+                accounts.frank,
+            ];
+            set_caller(accounts.bob);
+            assert_eq!(
+                casino.batch_process_exits(0, batch.clone()),
+                Err(Error::NotKeeper)
+            );
+            set_caller(accounts.alice);
+            assert_eq!(
+                casino.batch_process_exits(0, vec![accounts.bob; MAX_BATCH_SIZE + 1]),
+                Err(Error::BatchTooLarge)
+            );
+            assert_eq!(casino.batch_process_exits(0, batch), Ok(1));
+            let bob = casino.players.get((1, accounts.bob)).unwrap();
+            assert!(bob.exited);
+            assert_eq!(bob.pending_exits[0].multiplier, 120);
+            assert!(!casino.players.get((1, accounts.charlie)).unwrap().exited);
+            assert!(!casino.players.get((1, accounts.django)).unwrap().exited);
+        }
+
+        fn instant_bust_rate(casino: &CrashCasino, game: &Game, samples: u64) -> u64 {
+            let mut busts = 0;
+            for index in 0..samples {
+                let mut probe = game.clone();
+                probe.id = index + 1;
+                casino.roll_crash(&mut probe, None);
+                if probe.crash_threshold <= MULTIPLIER_BASE {
+                    busts += 1;
+                }
+            }
+            busts * 10_000 / samples
+        }
+
+        #[ink::test]
+        fn crash_probability_sets_the_empirical_instant_bust_rate() {
+            let mut casino = open_casino(0);
+            assert_eq!(
+                casino.set_crash_probability(10_001),
+                Err(Error::InvalidProbability)
+            );
+            casino.set_crash_probability(2_500).unwrap();
+            assert_eq!(casino.get_crash_probability(), 2_500);
+            resolve(&mut casino);
+
+            let samples = 20_000;
+            let baseline = instant_bust_rate(&casino, &casino.get_game(1).unwrap(), samples);
+            assert!(baseline < 300, "curve alone busts at {baseline} bps");
+            let game = casino.get_game(2).unwrap();
+            assert_eq!(game.crash_probability_bps, 2_500);
+            let rate = instant_bust_rate(&casino, &game, samples);
+            let expected = 2_500 + 7_500 * baseline / 10_000;
+            assert!(
+                rate.abs_diff(expected) <= 150,
+                "configured 2500 bps, observed {rate} bps, expected {expected} bps"
+            );
+
+            let mut certain = game.clone();
+            certain.crash_probability_bps = 10_000;
+            assert_eq!(instant_bust_rate(&casino, &certain, 1_000), 10_000);
+        }
+
+        #[ink::test]
+        fn game_interval_is_bounded_in_the_constructor_and_setter() {
+            set_caller(accounts().alice);
+            for (interval, valid) in [
+                (0, false),
+                (1, true),
+                (MAX_GAME_INTERVAL, true),
+                (MAX_GAME_INTERVAL + 1, false),
+            ] {
+                let result = CrashCasino::new(interval, None, SCALE, SCALE);
+                assert_eq!(result.is_ok(), valid, "constructor with {interval}");
+                if !valid {
+                    assert!(matches!(result, Err(Error::InvalidInterval)));
+                }
+            }
+            let mut casino = open_casino(0);
+            assert_eq!(casino.set_game_interval(0, 0), Err(Error::InvalidInterval));
+            assert_eq!(
+                casino.set_game_interval(0, MAX_GAME_INTERVAL + 1),
+                Err(Error::InvalidInterval)
+            );
+            assert_eq!(casino.set_game_interval(0, 1), Ok(()));
+            assert_eq!(casino.set_game_interval(0, MAX_GAME_INTERVAL), Ok(()));
+            assert_eq!(casino.get_game_interval(0), Some(MAX_GAME_INTERVAL));
+            assert_eq!(casino.create_game_track(0), Err(Error::InvalidInterval));
+            assert_eq!(casino.create_game_track(MAX_GAME_INTERVAL), Ok(1));
+        }
+
+        #[ink::test]
+        fn positions_left_in_a_cashed_game_stay_reserved() {
+            let mut casino = open_casino(10_000);
+            let bob = accounts().bob;
+            enter(&mut casino, bob, 1_000).unwrap();
+            rig(&mut casino, 1, 500..=u32::MAX);
+            resolve(&mut casino);
+            resolve(&mut casino);
+            assert_eq!(casino.get_current_game(0).unwrap().id, 3);
+
+            set_caller(accounts().alice);
+            assert_eq!(casino.withdraw(9_001), Err(Error::ReserveBreached));
+            casino.withdraw(9_000).unwrap();
+            set_caller(bob);
+            casino.exit_game(1).unwrap();
+            assert_eq!(casino.get_pending_withdrawal(bob), 2_000);
+            assert_eq!(casino.claim_reserve, 0);
+        }
+
+        #[ink::test]
+        fn bets_within_the_cooldown_are_rejected() {
+            let mut casino = open_casino(10_000);
+            casino.set_bet_cooldown(3).unwrap();
+            let bob = accounts().bob;
+            enter(&mut casino, bob, 1_000).unwrap();
+            advance(2);
+            assert_eq!(enter(&mut casino, bob, 1_000), Err(Error::Cooldown));
+            assert_eq!(enter(&mut casino, accounts().charlie, 1_000), Ok(()));
+            advance(1);
+            assert_eq!(enter(&mut casino, bob, 1_000), Ok(()));
+        }
+
+        #[ink::test]
+        fn entry_delay_and_liquidation_threshold_are_bounded() {
+            let mut casino = open_casino(10_000);
+            assert_eq!(
+                casino.set_entry_delay(INTERVAL),
+                Err(Error::InvalidInterval)
+            );
+            casino.set_entry_delay(INTERVAL - 1).unwrap();
+            assert_eq!(
+                casino.set_game_interval(0, INTERVAL - 1),
+                Err(Error::InvalidInterval)
+            );
+            assert_eq!(
+                casino.create_game_track(INTERVAL - 1),
+                Err(Error::InvalidInterval)
+            );
+            assert_eq!(
+                casino.set_liquidation_threshold(0),
+                Err(Error::InvalidInterval)
+            );
+            casino.set_liquidation_threshold(1).unwrap();
+        }
+
+        #[ink::test]
+        fn entry_closes_with_the_window_and_while_paused() {
+            let mut casino = open_casino(10_000);
+            assert!(casino.is_entry_open(0));
+            casino.pause().unwrap();
+            assert!(!casino.is_entry_open(0));
+            casino.unpause().unwrap();
+            advance(INTERVAL);
+            assert!(!casino.is_entry_open(0));
+            assert!(!casino.is_entry_open(1));
+        }
+
+        #[ink::test]
+        fn jackpot_pays_a_participant_and_resets() {
+            let mut casino = open_casino(10_000);
+            casino.set_jackpot_bps(1_000).unwrap();
+            casino.set_jackpot_probability(10_000).unwrap();
+            let bob = accounts().bob;
+            enter(&mut casino, bob, 1_000).unwrap();
+            assert_eq!(casino.get_jackpot_pool(), 100);
+            rig(&mut casino, 1, 100..=150);
+
+            resolve(&mut casino);
+            assert!(casino.get_game(1).unwrap().crashed);
+            assert_eq!(casino.get_jackpot_pool(), 0);
+            assert_eq!(casino.get_pending_withdrawal(bob), 100);
+        }
+
+        #[ink::test]
+        fn sweeping_an_old_crashed_game_settles_what_is_owed_first() {
+            let mut casino = open_casino(10_000);
+            let accounts = accounts();
+            enter(&mut casino, accounts.bob, 1_000).unwrap();
+            enter(&mut casino, accounts.charlie, 1_000).unwrap();
+            rig(&mut casino, 1, 130..=150);
+            advance(2);
+            set_caller(accounts.bob);
+            casino.exit_game(1).unwrap();
+            resolve(&mut casino);
+            assert!(casino.get_game(1).unwrap().crashed);
+
+            set_caller(accounts.alice);
+            assert_eq!(casino.sweep_game(1), Err(Error::GameTooRecent));
+            assert_eq!(casino.sweep_game(2), Err(Error::GameNotCrashed));
+            advance(SWEEP_DELAY);
+            assert_eq!(casino.sweep_game(1), Ok(2));
+            assert_eq!(casino.get_participant_count(1), 0);
+            assert_eq!(casino.players.get((1, accounts.charlie)), None);
+            assert_eq!(casino.get_pending_withdrawal(accounts.bob), 1_200);
+            assert_eq!(casino.get_pending_withdrawal(accounts.charlie), 0);
+        }
+
+        #[ink::test]
+        fn quotes_match_what_an_exit_pays() {
+            let mut casino = open_casino(10_000);
+            casino.set_house_fee(100).unwrap();
+            casino.set_max_payout_per_player(1_100).unwrap();
+            let accounts = accounts();
+            enter(&mut casino, accounts.bob, 1_000).unwrap();
+            rig(&mut casino, 1, 500..=u32::MAX);
+            advance(2);
+            assert_eq!(casino.quote_exit(1, accounts.bob), Some(1_089));
+            assert_eq!(casino.quote_exit(1, accounts.charlie), None);
+
+            set_caller(accounts.bob);
+            casino.exit_game(1).unwrap();
+            assert_eq!(casino.quote_exit(1, accounts.bob), None);
+            resolve(&mut casino);
+            assert_eq!(casino.settle_position(1, accounts.bob), Ok(1_089));
+        }
+
+        fn last_event<E: Decode>() -> E {
+            let event = test::recorded_events()
+                .last()
+                .expect("an event was emitted");
+            E::decode(&mut &event.data[..]).expect("the last event has this type")
+        }
+
+        #[ink::test]
+        fn configuration_changes_are_logged_with_the_old_and_new_value() {
+            let mut casino = open_casino(10_000);
+            casino.set_house_fee(250).unwrap();
+            let event: ConfigChanged = last_event();
+            assert_eq!(event.field, "house_fee_bps");
+            assert_eq!((event.old_value, event.new_value), (0, 250));
+
+            casino.set_game_interval(0, 20).unwrap();
+            let event: GameIntervalChanged = last_event();
+            assert_eq!(
+                (event.track_id, event.old_interval, event.new_interval),
+                (0, INTERVAL, 20)
+            );
+
+            let bob = accounts().bob;
+            casino.grant_admin(bob).unwrap();
+            let event: AccessChanged = last_event();
+            assert_eq!(
+                (event.account, event.field.as_str(), event.granted),
+                (bob, "admin", true)
+            );
+        }
+
+        #[ink::test]
+        fn entering_before_the_first_game_reports_no_game_started() {
+            set_caller(accounts().alice);
+            let mut casino = CrashCasino::new(INTERVAL, None, SCALE, SCALE).unwrap();
+            assert_eq!(
+                enter(&mut casino, accounts().bob, 1_000),
+                Err(Error::NoGameStarted)
+            );
+            assert_eq!(casino.get_current_game(0), None);
+        }
+
+        #[ink::test]
+        fn resolutions_in_the_same_block_draw_different_entropy() {
+            let mut casino = open_casino(10_000);
+            advance(INTERVAL * 3);
+            assert_eq!(casino.catch_up(0, 2), Ok(2));
+            let first = casino.get_game(1).unwrap();
+            let second = casino.get_game(2).unwrap();
+            assert_eq!(first.resolution_block, second.resolution_block);
+            assert_ne!(first.start_nonce, second.start_nonce);
+            assert_ne!(first.resolution_entropy, second.resolution_entropy);
+        }
+
+        #[ink::test]
+        fn game_pool_fills_to_the_cap_then_rejects() {
+            let mut casino = open_casino(10_000);
+            casino.set_max_game_pool(2_000).unwrap();
+            assert_eq!(casino.get_max_game_pool(), 2_000);
+            let accounts = accounts();
+            enter(&mut casino, accounts.bob, 1_500).unwrap();
+            enter(&mut casino, accounts.charlie, 500).unwrap();
+            assert_eq!(casino.get_game(1).unwrap().game_pool, 2_000);
+            assert_eq!(
+                enter(&mut casino, accounts.django, 1),
+                Err(Error::GamePoolFull)
+            );
+        }
+
+        #[ink::test]
+        fn the_net_house_position_goes_negative_when_players_win() {
+            let mut casino = open_casino(10_000);
+            let bob = accounts().bob;
+            enter(&mut casino, bob, 1_000).unwrap();
+            rig(&mut casino, 1, 500..=u32::MAX);
+            assert_eq!(casino.get_net_house_position(), 1_000);
+            advance(2);
+            set_caller(bob);
+            casino.exit_game(1).unwrap();
+            resolve(&mut casino);
+            casino.settle_position(1, bob).unwrap();
+            assert_eq!(casino.get_net_house_position(), -200);
+        }
+
+        #[ink::test]
+        fn auto_cashout_targets_can_be_changed_and_cancelled() {
+            let mut casino = open_casino(10_000);
+            let accounts = accounts();
+            set_caller(accounts.bob);
+            assert_eq!(casino.set_auto_cashout(0, SCALE * 2), Err(Error::NotInGame));
+            assert_eq!(casino.cancel_auto_cashout(0), Err(Error::NotInGame));
+
+            enter(&mut casino, accounts.bob, 1_000).unwrap();
+            casino.set_auto_cashout(0, SCALE * 2).unwrap();
+            casino.set_auto_cashout(0, SCALE * 3).unwrap();
+            let player = casino.players.get((1, accounts.bob)).unwrap();
+            assert_eq!(player.auto_cashout_price, Some(SCALE * 3));
+            assert_eq!(casino.auto_cashouts.get(1).unwrap(), vec![accounts.bob]);
+
+            casino.cancel_auto_cashout(0).unwrap();
+            let player = casino.players.get((1, accounts.bob)).unwrap();
+            assert_eq!(player.auto_cashout_price, None);
+            assert_eq!(casino.cancel_auto_cashout(0), Err(Error::InvalidTarget));
+
+            casino.exit_game(1).unwrap();
+            assert_eq!(
+                casino.set_auto_cashout(0, SCALE * 2),
+                Err(Error::AlreadyExited)
+            );
+            assert_eq!(casino.cancel_auto_cashout(0), Err(Error::AlreadyExited));
+        }
+
+        #[ink::test]
+        fn resolution_waits_out_the_grace_period() {
+            let mut casino = open_casino(10_000);
+            casino.set_max_grace_blocks(5).unwrap();
+            enter(&mut casino, accounts().bob, 1_000).unwrap();
+            advance(INTERVAL);
+            let due = block();
+            set_caller(accounts().alice);
+            casino.tick().unwrap();
+            let game = casino.get_game(1).unwrap();
+            assert!(game.crash_block >= due && game.crash_block <= due + 5);
+            while block() < game.crash_block {
+                assert_eq!(casino.get_game_state(1), Some(GameState::Resolving));
+                assert_eq!(casino.get_total_games(), 1);
+                advance(1);
+                casino.tick().unwrap();
+            }
+            assert_ne!(casino.get_game_state(1), Some(GameState::Resolving));
+            assert_eq!(casino.get_total_games(), 2);
+        }
+
+        #[ink::test]
+        fn a_position_is_valued_at_the_live_price() {
+            let mut casino = open_casino(10_000);
+            let bob = accounts().bob;
+            set_caller(bob);
+            assert_eq!(casino.get_my_value(0), None);
+            enter(&mut casino, bob, 1_000).unwrap();
+            assert_eq!(casino.get_my_value(0), Some(1_000));
+            advance(5);
+            assert_eq!(casino.get_my_value(0), Some(1_500));
+            casino.exit_game(1).unwrap();
+            assert_eq!(casino.get_my_value(0), None);
+        }
+
+        #[ink::test]
+        fn last_standing_pays_the_positions_still_in_at_the_crash() {
+            let mut casino = open_casino(10_000);
+            casino.set_game_mode(0, GameMode::LastStanding).unwrap();
+            casino.set_last_standing_winners(2).unwrap();
+            casino.set_jackpot_bps(1_000).unwrap();
+            casino.set_house_fee(1_000).unwrap();
+            resolve(&mut casino);
+            let accounts = accounts();
+            let targets = [
+                (accounts.bob, Some(120), 1_000),
+                (accounts.charlie, Some(140), 1_000),
+                (accounts.django, Some(180), 1_000),
+                (accounts.eve, None, 500),
+                (accounts.frank, Some(110), 1_500),
+            ];
+            for (account, target, stake) in targets {
+                enter(&mut casino, account, stake).unwrap();
+                if let Some(target) = target {
+                    casino.set_auto_cashout(0, SCALE * target / 100).unwrap();
+                }
+            }
+            assert_eq!(casino.quote_exit(2, accounts.bob), None);
+            assert_eq!(casino.exit_game(2), Err(Error::ExitsDisabled));
+            rig(&mut casino, 2, 150..=160);
+
+            resolve(&mut casino);
+            let game = casino.get_game(2).unwrap();
+            assert!(game.crashed);
+            assert_eq!(game.open_positions, 0);
+            assert_eq!(
+                casino.settle_position(2, accounts.bob),
+                Err(Error::ExitsDisabled)
+            );
+            // 5_000 staked less the 500 jackpot cut leaves 2_250 a winner, less the fee.
+            assert_eq!(casino.get_pending_withdrawal(accounts.django), 2_025);
+            assert_eq!(casino.get_pending_withdrawal(accounts.eve), 2_025);
+            assert_eq!(casino.get_house_revenue(), 450);
+            for loser in [accounts.bob, accounts.charlie, accounts.frank] {
+                assert_eq!(casino.get_pending_withdrawal(loser), 0);
+                assert!(casino.players.get((2, loser)).unwrap().settled);
+                assert_eq!(casino.get_loss_streak(loser), 1);
+            }
+        }
+
+        #[ink::test]
+        fn an_inconsistent_pool_fails_the_payout_instead_of_wrapping() {
+            let mut casino = open_casino(10_000);
+            let bob = accounts().bob;
+            enter(&mut casino, bob, 1_000).unwrap();
+            rig(&mut casino, 1, 500..=u32::MAX);
+            resolve(&mut casino);
+            // Drift the pool below what the game still owes.
+            casino.casino_pool = 1_999;
+            set_caller(bob);
+            assert_eq!(casino.exit_game(1), Err(Error::InconsistentAccounting));
+            assert_eq!(casino.get_casino_pool(), 1_999);
+            assert_eq!(casino.get_pending_withdrawal(bob), 0);
+
+            casino.casino_pool = 12_000;
+            enter(&mut casino, bob, 1_000).unwrap();
+            set_caller(accounts().alice);
+            casino.cancel_game(2).unwrap();
+            casino.casino_pool = 999;
+            set_caller(bob);
+            assert_eq!(casino.claim_refund(2), Err(Error::InconsistentAccounting));
+            assert_eq!(casino.get_casino_pool(), 999);
+        }
+
+        #[ink::test]
+        fn account_exposure_fills_to_the_limit_then_rejects() {
+            let mut casino = open_casino(10_000);
+            assert_eq!(casino.get_max_account_exposure(), 0);
+            casino.set_max_account_exposure(2_000).unwrap();
+            let accounts = accounts();
+            enter(&mut casino, accounts.bob, 1_500).unwrap();
+            enter(&mut casino, accounts.bob, 500).unwrap();
+            assert_eq!(
+                enter(&mut casino, accounts.bob, 1),
+                Err(Error::ExposureLimit)
+            );
+            assert_eq!(enter(&mut casino, accounts.charlie, 2_000), Ok(()));
+        }
+
+        #[ink::test]
+        fn catch_up_resolves_three_missed_games() {
+            let mut casino = open_casino(10_000);
+            enter(&mut casino, accounts().bob, 1_000).unwrap();
+            let start = casino.get_game(1).unwrap().start_block;
+            advance(INTERVAL * 4);
+            set_caller(accounts().alice);
+            assert_eq!(casino.catch_up(0, 3), Ok(3));
+            for game_id in 1..=3u64 {
+                let game = casino.get_game(game_id).unwrap();
+                assert!(matches!(game.state, GameState::Crashed | GameState::Cashed));
+                assert_eq!(game.start_block, start + INTERVAL * (game_id as u32 - 1));
+                assert_eq!(game.resolution_block, block());
+            }
+            let current = casino.get_current_game(0).unwrap();
+            assert_eq!(current.id, 4);
+            assert_eq!(current.start_block, start + INTERVAL * 3);
+            assert_eq!(current.state, GameState::Open);
+        }
+
+        #[ink::test]
+        fn game_status_lists_who_is_in_and_who_left() {
+            let mut casino = open_casino(10_000);
+            let accounts = accounts();
+            enter(&mut casino, accounts.bob, 1_000).unwrap();
+            enter(&mut casino, accounts.charlie, 500).unwrap();
+            set_caller(accounts.bob);
+            casino.exit_game(1).unwrap();
+            assert_eq!(
+                casino.get_game_status(1),
+                vec![(accounts.bob, 0, true), (accounts.charlie, 500, false)]
+            );
+            assert!(casino.get_game_status(2).is_empty());
+        }
+
+        #[ink::test]
+        fn force_crash_pays_earlier_exits_and_leaves_resolving_games_alone() {
+            let mut casino = open_casino(10_000);
+            casino.set_max_grace_blocks(5).unwrap();
+            let accounts = accounts();
+            enter(&mut casino, accounts.bob, 1_000).unwrap();
+            enter(&mut casino, accounts.charlie, 1_000).unwrap();
+            advance(2);
+            set_caller(accounts.bob);
+            casino.exit_game(1).unwrap();
+            advance(3);
+            set_caller(accounts.alice);
+            casino.force_crash(0).unwrap();
+            let game = casino.get_game(1).unwrap();
+            assert!(game.crashed);
+            assert_eq!(game.crash_multiplier, 150);
+            assert_eq!(casino.force_crash(0), Err(Error::GameCrashed));
+            assert_eq!(casino.settle_position(1, accounts.bob), Ok(1_200));
+            assert_eq!(casino.settle_position(1, accounts.charlie), Ok(0));
+
+            advance(INTERVAL);
+            casino.tick().unwrap();
+            enter(&mut casino, accounts.bob, 1_000).unwrap();
+            advance(INTERVAL);
+            set_caller(accounts.alice);
+            casino.tick().unwrap();
+            assert_eq!(casino.get_game(2).unwrap().final_multiplier, 0);
+            assert_eq!(casino.force_crash(0), Err(Error::GameResolved));
+        }
+
+        #[ink::test]
+        fn wallet_balances_fund_entries_until_withdrawn() {
+            let mut casino = open_casino(10_000);
+            let bob = accounts().bob;
+            set_caller(bob);
+            set_value(1_000);
+            casino.deposit_balance().unwrap();
+            set_value(0);
+            casino.enter_game_from_balance(0, 600).unwrap();
+            assert_eq!(casino.get_game(1).unwrap().game_pool, 600);
+            assert_eq!(casino.get_wallet_balance(bob), 400);
+
+            assert_eq!(
+                casino.withdraw_balance(500),
+                Err(Error::InsufficientBalance)
+            );
+            let before = balance(bob);
+            casino.withdraw_balance(400).unwrap();
+            assert_eq!(balance(bob), before + 400);
+            assert_eq!(casino.get_wallet_balance(bob), 0);
+        }
+
+        #[ink::test]
+        fn fee_tiers_pick_the_rate_by_deposit_size() {
+            let mut casino = open_casino(10_000);
+            assert_eq!(
+                casino.set_fee_tiers(vec![(1_000, 500), (1_000, 100)]),
+                Err(Error::InvalidFeeTiers)
+            );
+            let tiers = vec![(0, 1_000), (1_000, 500), (5_000, 100)];
+            casino.set_fee_tiers(tiers.clone()).unwrap();
+            let event: FeeTiersChanged = last_event();
+            assert!(event.old_tiers.is_empty());
+            assert_eq!(event.new_tiers, tiers);
+
+            let accounts = accounts();
+            let stakes = [
+                (accounts.bob, 500),
+                (accounts.charlie, 1_000),
+                (accounts.django, 5_000),
+            ];
+            for (account, stake) in stakes {
+                enter(&mut casino, account, stake).unwrap();
+            }
+            rig(&mut casino, 1, 130..=150);
+            advance(2);
+            for (account, _) in stakes {
+                set_caller(account);
+                casino.exit_game(1).unwrap();
+            }
+            resolve(&mut casino);
+            for (account, _) in stakes {
+                set_caller(account);
+                casino.exit_game(1).unwrap();
+            }
+            assert_eq!(casino.get_pending_withdrawal(accounts.bob), 540);
+            assert_eq!(casino.get_pending_withdrawal(accounts.charlie), 1_140);
+            assert_eq!(casino.get_pending_withdrawal(accounts.django), 5_940);
+        }
+
+        #[ink::test]
+        fn mode_and_rebate_pool_changes_emit_events() {
+            let mut casino = open_casino(0);
+            casino.set_game_mode(0, GameMode::LastStanding).unwrap();
+            let event: GameModeChanged = last_event();
+            assert_eq!(event.track_id, 0);
+            assert_eq!(event.old_mode, GameMode::CashOut);
+            assert_eq!(event.new_mode, GameMode::LastStanding);
+
+            set_value(300);
+            casino.fund_rebate_pool().unwrap();
+            set_value(0);
+            let event: RebatePoolFunded = last_event();
+            assert_eq!(event.from, accounts().alice);
+            assert_eq!(event.amount, 300);
+        }
+
+        #[ink::test]
+        fn early_bonus_pays_only_positions_that_ride_to_resolution() {
+            let mut casino = open_casino(10_000);
+            casino.set_early_bonus(3, 1_000).unwrap();
+            let accounts = accounts();
+            enter(&mut casino, accounts.bob, 1_000).unwrap();
+            enter(&mut casino, accounts.django, 1_000).unwrap();
+            casino.exit_game(1).unwrap();
+            advance(5);
+            enter(&mut casino, accounts.charlie, 1_500).unwrap();
+            assert_eq!(
+                casino.players.get((1, accounts.bob)).unwrap().bonus_tokens,
+                100
+            );
+            assert_eq!(
+                casino
+                    .players
+                    .get((1, accounts.charlie))
+                    .unwrap()
+                    .bonus_tokens,
+                0
+            );
+
+            rig(&mut casino, 1, 500..=u32::MAX);
+            resolve(&mut casino);
+            for account in [accounts.bob, accounts.charlie, accounts.django] {
+                set_caller(account);
+                casino.exit_game(1).unwrap();
+            }
+            assert_eq!(casino.get_pending_withdrawal(accounts.bob), 2_200);
+            assert_eq!(casino.get_pending_withdrawal(accounts.charlie), 2_000);
+            // Leaving in the entry block returns the stake without the bonus.
+            assert_eq!(casino.get_pending_withdrawal(accounts.django), 1_000);
+        }
+
+        #[ink::test]
+        fn only_settled_positions_can_be_cleared() {
+            let mut casino = open_casino(10_000);
+            let accounts = accounts();
+            enter(&mut casino, accounts.bob, 1_000).unwrap();
+            enter(&mut casino, accounts.charlie, 1_000).unwrap();
+            rig(&mut casino, 1, 130..=150);
+            advance(2);
+            set_caller(accounts.bob);
+            casino.exit_game(1).unwrap();
+            assert_eq!(casino.clear_position(1), Err(Error::PositionUnsettled));
+            resolve(&mut casino);
+
+            set_caller(accounts.bob);
+            assert_eq!(casino.clear_position(1), Err(Error::PositionUnsettled));
+            casino.exit_game(1).unwrap();
+            assert_eq!(casino.clear_position(1), Ok(()));
+            set_caller(accounts.charlie);
+            assert_eq!(casino.clear_position(1), Ok(()));
+            assert_eq!(casino.players.get((1, accounts.bob)), None);
+            assert_eq!(casino.players.get((1, accounts.charlie)), None);
+            assert_eq!(casino.get_pending_withdrawal(accounts.bob), 1_200);
+        }
+
+        #[ink::test]
+        fn keeper_reward_is_paid_once_per_resolution_with_players() {
+            let mut casino = open_casino(10_000);
+            casino.set_keeper_reward(10).unwrap();
+            assert_eq!(casino.get_keeper_reward(), 10);
+            let eve = accounts().eve;
+            enter(&mut casino, accounts().bob, 1_000).unwrap();
+            advance(INTERVAL);
+            set_caller(eve);
+            casino.tick().unwrap();
+            casino.tick().unwrap();
+            assert_eq!(casino.get_pending_withdrawal(eve), 10);
+
+            // Game 2 has no entrants, so resolving it earns nothing.
+            advance(INTERVAL);
+            casino.tick().unwrap();
+            assert_eq!(casino.get_total_games(), 3);
+            assert_eq!(casino.get_pending_withdrawal(eve), 10);
+        }
+
+        #[ink::test]
+        fn game_summaries_page_through_the_games_played() {
+            let mut casino = open_casino(10_000);
+            enter(&mut casino, accounts().bob, 1_000).unwrap();
+            rig(&mut casino, 1, 130..=150);
+            resolve(&mut casino);
+            let summaries = casino.get_game_summaries(1, 5);
+            assert_eq!(
+                summaries,
+                vec![
+                    GameSummary {
+                        id: 1,
+                        crashed: true,
+                        game_pool: 1_000,
+                    },
+                    GameSummary {
+                        id: 2,
+                        crashed: false,
+                        game_pool: 0,
+                    },
+                ]
+            );
+            assert_eq!(casino.get_game_summaries(2, 1).len(), 1);
+            assert!(casino.get_game_summaries(3, 5).is_empty());
+        }
+
+        #[ink::test]
+        fn zero_addresses_are_rejected() {
+            let mut casino = open_casino(0);
+            let zero = AccountId::from([0u8; 32]);
+            assert_eq!(casino.set_treasury(zero), Err(Error::InvalidAddress));
+            assert_eq!(casino.transfer_ownership(zero), Err(Error::InvalidAddress));
+            assert_eq!(casino.grant_admin(zero), Err(Error::InvalidAddress));
+            assert_eq!(casino.get_treasury(), accounts().alice);
+            assert_eq!(casino.get_pending_owner(), None);
+            assert!(!casino.is_admin(zero));
+        }
+
+        #[ink::test]
+        fn entries_wait_out_the_entry_delay() {
+            let mut casino = open_casino(10_000);
+            casino.set_entry_delay(2).unwrap();
+            let bob = accounts().bob;
+            assert!(!casino.is_entry_open(0));
+            assert_eq!(enter(&mut casino, bob, 1_000), Err(Error::EntryNotOpen));
+            advance(2);
+            assert_eq!(enter(&mut casino, bob, 1_000), Err(Error::EntryNotOpen));
+            advance(1);
+            assert!(casino.is_entry_open(0));
+            assert_eq!(enter(&mut casino, bob, 1_000), Ok(()));
+        }
+
+        #[ink::test]
+        fn solvency_counts_open_positions_and_reserved_exits() {
+            let mut casino = open_casino(10_000);
+            let accounts = accounts();
+            enter(&mut casino, accounts.bob, 1_000).unwrap();
+            enter(&mut casino, accounts.charlie, 1_000).unwrap();
+            advance(5);
+            assert_eq!(casino.check_solvency(), (12_000, 3_000, true));
+            set_caller(accounts.bob);
+            casino.exit_game(1).unwrap();
+            advance(5);
+            assert_eq!(casino.check_solvency(), (12_000, 3_500, true));
+        }
+
+        #[ink::test]
+        fn reaching_the_ceiling_cashes_every_holder_at_the_ceiling() {
+            let mut casino = open_casino(10_000);
+            casino.set_max_multiplier(150).unwrap();
+            let accounts = accounts();
+            enter(&mut casino, accounts.bob, 1_000).unwrap();
+            enter(&mut casino, accounts.charlie, 2_000).unwrap();
+            rig(&mut casino, 1, 160..=u32::MAX);
+            advance(7);
+            assert_eq!(casino.get_current_multiplier(0), 150);
+
+            resolve(&mut casino);
+            let game = casino.get_game(1).unwrap();
+            assert_eq!(game.state, GameState::Cashed);
+            assert!(!game.crashed);
+            assert_eq!(game.final_multiplier, 150);
+            for account in [accounts.bob, accounts.charlie] {
+                casino.settle_position(1, account).unwrap();
+            }
+            assert_eq!(casino.get_pending_withdrawal(accounts.bob), 1_500);
+            assert_eq!(casino.get_pending_withdrawal(accounts.charlie), 3_000);
+        }
+
+        #[ink::test]
+        fn a_new_casino_reports_the_current_versions() {
+            let casino = open_casino(0);
+            assert_eq!(casino.get_version(), CONTRACT_VERSION);
+            assert_eq!(casino.get_schema_version(), SCHEMA_VERSION);
+        }
+
+        #[ink::test]
+        fn checked_entries_fail_once_the_game_rolls_over() {
+            let mut casino = open_casino(10_000);
+            set_caller(accounts().bob);
+            set_value(1_000);
+            assert_eq!(casino.enter_game_checked(0, 1), Ok(()));
+            advance(INTERVAL);
+            casino.tick().unwrap();
+            assert_eq!(casino.enter_game_checked(0, 1), Err(Error::GameChanged));
+            assert_eq!(casino.enter_game_checked(0, 2), Ok(()));
+            set_value(0);
+        }
+
+        #[ink::test]
+        fn free_liquidity_excludes_what_open_positions_are_worth() {
+            let mut casino = open_casino(10_000);
+            assert_eq!(casino.get_free_liquidity(), 10_000);
+            enter(&mut casino, accounts().bob, 1_000).unwrap();
+            advance(5);
+            assert_eq!(casino.get_free_liquidity(), 9_500);
+            casino.exit_game(1).unwrap();
+            assert_eq!(casino.get_free_liquidity(), 9_500);
+        }
+
+        #[ink::test]
+        fn rounding_favours_the_pool_at_every_boundary() {
+            set_caller(accounts().alice);
+            let mut casino = CrashCasino::new(INTERVAL, None, 3, 10).unwrap();
+            // 10 buys 33.3 tokens: the fraction is dropped, not rounded up.
+            assert_eq!(casino.tokens_for(10, 3), Ok(33));
+            assert_eq!(casino.tokens_for(9, 3), Ok(30));
+            // 33 tokens are worth 9.9: payouts round down too.
+            assert_eq!(casino.value_of(33, 3), Ok(9));
+            assert_eq!(casino.value_of(30, 3), Ok(9));
+            // Fees round up, so the net payout still rounds down.
+            assert_eq!(CrashCasino::fee_on(999, 100), 10);
+            assert_eq!(CrashCasino::fee_on(1_000, 100), 10);
+            assert_eq!(CrashCasino::fee_on(1, 1), 1);
+
+            advance(INTERVAL);
+            casino.tick().unwrap();
+            enter(&mut casino, accounts().bob, 10).unwrap();
+            enter(&mut casino, accounts().charlie, 9).unwrap();
+            assert_eq!(casino.get_rounding_dust(), 1);
+        }
+
+        #[ink::test]
+        fn the_crash_point_is_fixed_at_the_start_and_hidden_until_resolution() {
+            let mut casino = open_casino(10_000);
+            let rolled = casino.games.get(1).unwrap();
+            assert!(rolled.crash_threshold >= MULTIPLIER_BASE);
+            enter(&mut casino, accounts().bob, 1_000).unwrap();
+            advance(5);
+            let game = casino.get_game(1).unwrap();
+            assert_eq!(game.crash_threshold, 0);
+            assert_eq!(game.resolution_entropy, 0);
+            assert_eq!(casino.get_current_game(0).unwrap().crash_threshold, 0);
+            assert_eq!(casino.get_recent_games(1, 1)[0].crash_threshold, 0);
+            assert!(casino.verify_game(1).is_none());
+
+            // However late and by whom the game is resolved, the roll stands.
+            advance(INTERVAL + 7);
+            set_caller(accounts().eve);
+            casino.tick().unwrap();
+            let game = casino.get_game(1).unwrap();
+            assert_eq!(game.crash_threshold, rolled.crash_threshold);
+            assert_eq!(game.resolution_entropy, rolled.resolution_entropy);
+            assert!(casino.verify_game(1).is_some());
+        }
+
+        #[ink::test]
+        fn the_leaderboard_ranks_accounts_by_total_winnings() {
+            let mut casino = open_casino(10_000);
+            let accounts = accounts();
+            enter(&mut casino, accounts.bob, 1_000).unwrap();
+            enter(&mut casino, accounts.charlie, 2_000).unwrap();
+            rig(&mut casino, 1, 500..=u32::MAX);
+            resolve(&mut casino);
+            for account in [accounts.bob, accounts.charlie] {
+                casino.settle_position(1, account).unwrap();
+            }
+            assert_eq!(
+                casino.get_leaderboard(),
+                vec![(accounts.charlie, 4_000), (accounts.bob, 2_000)]
+            );
+
+            enter(&mut casino, accounts.bob, 2_000).unwrap();
+            rig(&mut casino, 2, 500..=u32::MAX);
+            resolve(&mut casino);
+            casino.settle_position(2, accounts.bob).unwrap();
+            assert_eq!(
+                casino.get_leaderboard(),
+                vec![(accounts.bob, 6_000), (accounts.charlie, 4_000)]
+            );
+        }
+
+        #[ink::test]
+        fn entries_on_behalf_of_another_account_pay_the_beneficiary() {
+            let mut casino = open_casino(10_000);
+            let accounts = accounts();
+            casino.set_blacklisted(accounts.django, true).unwrap();
+            set_caller(accounts.charlie);
+            set_value(1_000);
+            assert_eq!(
+                casino.enter_game_for(0, accounts.django),
+                Err(Error::Blacklisted)
+            );
+            casino.enter_game_for(0, accounts.bob).unwrap();
+            set_value(0);
+            assert!(casino.players.get((1, accounts.charlie)).is_none());
+            assert_eq!(
+                casino
+                    .players
+                    .get((1, accounts.bob))
+                    .unwrap()
+                    .total_deposited,
+                1_000
+            );
+
+            rig(&mut casino, 1, 500..=u32::MAX);
+            resolve(&mut casino);
+            set_caller(accounts.charlie);
+            assert_eq!(casino.exit_game(1), Err(Error::NotInGame));
+            set_caller(accounts.bob);
+            casino.exit_game(1).unwrap();
+            assert_eq!(casino.get_pending_withdrawal(accounts.bob), 2_000);
+            assert_eq!(casino.get_pending_withdrawal(accounts.charlie), 0);
+        }
+
+        #[ink::test]
+        fn without_auto_restart_the_track_idles_after_resolution() {
+            let mut casino = open_casino(10_000);
+            casino.set_auto_restart(false).unwrap();
+            enter(&mut casino, accounts().bob, 1_000).unwrap();
+            rig(&mut casino, 1, 500..=u32::MAX);
+            resolve(&mut casino);
+            assert_eq!(casino.get_game_state(1), Some(GameState::Cashed));
+            set_caller(accounts().bob);
+            assert_eq!(
+                casino.set_auto_cashout(0, 2 * SCALE),
+                Err(Error::GameResolved)
+            );
+            set_caller(accounts().alice);
+            assert_eq!(casino.get_total_games(), 1);
+            advance(INTERVAL);
+            casino.tick().unwrap();
+            assert_eq!(casino.get_total_games(), 1);
+
+            assert_eq!(casino.start_game(0), Ok(2));
+            assert_eq!(casino.get_current_game(0).unwrap().start_block, block());
+        }
+
+        #[ink::test]
+        fn error_codes_match_the_documented_table() {
+            let table = [
+                (Error::NoActiveGame, 0),
+                (Error::GameCrashed, 1),
+                (Error::ZeroFunds, 2),
+                (Error::BetTooSmall, 3),
+                (Error::BetTooLarge, 4),
+                (Error::InvalidBetLimits, 5),
+                (Error::InvalidFee, 6),
+                (Error::InsufficientRevenue, 7),
+                (Error::TransferFailed, 8),
+                (Error::ReserveBreached, 9),
+                (Error::NotPendingOwner, 10),
+                (Error::Paused, 11),
+                (Error::NotInGame, 12),
+                (Error::AlreadyExited, 13),
+                (Error::InvalidTarget, 14),
+                (Error::AutoCashoutQueueFull, 15),
+                (Error::InsufficientFunds, 16),
+                (Error::SeedAlreadyCommitted, 17),
+                (Error::GameAlreadyStarted, 18),
+                (Error::NoSeedCommitted, 19),
+                (Error::RevealTooEarly, 20),
+                (Error::SeedMismatch, 21),
+                (Error::SeedNotRevealed, 22),
+                (Error::SeedAlreadyRevealed, 23),
+                (Error::TokenNotConfigured, 24),
+                (Error::NativeBetsDisabled, 25),
+                (Error::TokenTransferFailed, 26),
+                (Error::GameCancelled, 27),
+                (Error::GameNotCancelled, 28),
+                (Error::GameResolved, 29),
+                (Error::GameExpired, 30),
+                (Error::NotKeeper, 31),
+                (Error::TrackNotFound, 32),
+                (Error::TooManyTracks, 33),
+                (Error::InsufficientTokens, 34),
+                (Error::ExitQueueFull, 35),
+                (Error::InvalidMultiplier, 36),
+                (Error::Overflow, 37),
+                (Error::Blacklisted, 38),
+                (Error::NotAllowed, 39),
+                (Error::SelfReferral, 40),
+                (Error::ReferralLoop, 41),
+                (Error::NoReferralRewards, 42),
+                (Error::NotStalled, 43),
+                (Error::InvalidInterval, 44),
+                (Error::InvalidPrice, 45),
+                (Error::InsufficientReserves, 46),
+                (Error::BatchTooLarge, 47),
+                (Error::NoWinnings, 48),
+                (Error::InvalidProbability, 49),
+                (Error::GameActive, 50),
+                (Error::Cooldown, 51),
+                (Error::Liquidated, 52),
+                (Error::OwnerActive, 53),
+                (Error::GameNotCrashed, 54),
+                (Error::GameTooRecent, 55),
+                (Error::NoGameStarted, 56),
+                (Error::GamePoolFull, 57),
+                (Error::ExitsDisabled, 58),
+                (Error::InvalidWinnerCount, 59),
+                (Error::GameFull, 60),
+                (Error::InconsistentAccounting, 61),
+                (Error::ExposureLimit, 62),
+                (Error::InsufficientBalance, 63),
+                (Error::InvalidFeeTiers, 64),
+                (Error::PositionUnsettled, 65),
+                (Error::InvalidAddress, 66),
+                (Error::EntryNotOpen, 67),
+                (Error::GameChanged, 68),
+                (Error::NotOwner, 69),
+                (Error::NotAdmin, 70),
+                (Error::InvalidPriceScale, 71),
+                (Error::InvalidConversionRate, 72),
+                (Error::PoolBelowMinimum, 73),
+                (Error::ContractsNotAllowed, 74),
+                (Error::InvalidSchedule, 75),
+                (Error::ScheduleQueueFull, 76),
+            ];
+            for (index, (error, code)) in table.into_iter().enumerate() {
+                assert_eq!(index, code as usize);
+                assert_eq!(error.encode(), vec![code], "{error:?}");
+            }
+        }
+
+        #[ink::test]
+        fn failing_paths_return_their_specific_variant() {
+            let mut casino = open_casino(10_000);
+            let accounts = accounts();
+            assert!(matches!(
+                CrashCasino::new(INTERVAL, None, SCALE, 1_500),
+                Err(Error::InvalidPriceScale)
+            ));
+            assert!(matches!(
+                CrashCasino::new(INTERVAL, None, 0, SCALE),
+                Err(Error::InvalidPrice)
+            ));
+            assert_eq!(casino.set_house_fee(10_001), Err(Error::InvalidFee));
+            assert_eq!(casino.set_bet_limits(10, 5), Err(Error::InvalidBetLimits));
+            casino.set_bet_limits(100, 200).unwrap();
+            assert_eq!(casino.tick_track(5), Err(Error::TrackNotFound));
+
+            set_caller(accounts.bob);
+            assert_eq!(casino.set_house_fee(0), Err(Error::NotAdmin));
+            assert_eq!(casino.withdraw(1), Err(Error::NotOwner));
+            assert_eq!(casino.accept_ownership(), Err(Error::NotPendingOwner));
+            assert_eq!(casino.withdraw_winnings(), Err(Error::NoWinnings));
+            assert_eq!(casino.exit_game(1), Err(Error::NotInGame));
+            assert_eq!(casino.exit_game(99), Err(Error::NoActiveGame));
+            assert_eq!(enter(&mut casino, accounts.bob, 0), Err(Error::ZeroFunds));
+            assert_eq!(
+                enter(&mut casino, accounts.bob, 50),
+                Err(Error::BetTooSmall)
+            );
+            assert_eq!(
+                enter(&mut casino, accounts.bob, 300),
+                Err(Error::BetTooLarge)
+            );
+
+            set_caller(accounts.alice);
+            casino.pause().unwrap();
+            assert_eq!(enter(&mut casino, accounts.bob, 150), Err(Error::Paused));
+            assert_eq!(casino.tick(), Err(Error::Paused));
+        }
+
+        #[ink::test]
+        fn holding_longer_earns_a_larger_payout_for_the_same_tokens() {
+            let mut casino = open_casino(10_000);
+            casino.set_holding_bonus(1_000).unwrap();
+            let accounts = accounts();
+            enter(&mut casino, accounts.bob, 1_000).unwrap();
+            advance(8);
+            enter(&mut casino, accounts.charlie, 1_800).unwrap();
+            assert_eq!(
+                casino.players.get((1, accounts.bob)).unwrap().token_balance,
+                casino
+                    .players
+                    .get((1, accounts.charlie))
+                    .unwrap()
+                    .token_balance
+            );
+
+            rig(&mut casino, 1, 500..=u32::MAX);
+            resolve(&mut casino);
+            casino.settle_position(1, accounts.bob).unwrap();
+            casino.settle_position(1, accounts.charlie).unwrap();
+            assert_eq!(casino.get_pending_withdrawal(accounts.bob), 2_200);
+            assert_eq!(casino.get_pending_withdrawal(accounts.charlie), 2_040);
+        }
+
+        #[ink::test]
+        fn a_top_up_is_held_only_from_when_it_was_bought() {
+            let mut casino = open_casino(10_000);
+            casino.set_holding_bonus(1_000).unwrap();
+            let accounts = accounts();
+            enter(&mut casino, accounts.bob, 1).unwrap();
+            advance(8);
+            enter(&mut casino, accounts.bob, 1_900).unwrap();
+            enter(&mut casino, accounts.charlie, 1_901).unwrap();
+            let entry = |account| casino.players.get((1, account)).unwrap().entry_block;
+            assert_eq!(entry(accounts.bob), entry(accounts.charlie));
+
+            rig(&mut casino, 1, 500..=u32::MAX);
+            resolve(&mut casino);
+            casino.settle_position(1, accounts.bob).unwrap();
+            casino.settle_position(1, accounts.charlie).unwrap();
+            assert_eq!(casino.get_pending_withdrawal(accounts.bob), 2_154);
+            assert_eq!(casino.get_pending_withdrawal(accounts.charlie), 2_154);
+        }
+
+        #[ink::test]
+        fn participant_slots_fill_then_only_existing_entrants_may_add() {
+            let mut casino = open_casino(10_000);
+            assert_eq!(casino.get_max_participants(), 0);
+            casino.set_max_participants(2).unwrap();
+            let accounts = accounts();
+            enter(&mut casino, accounts.bob, 1_000).unwrap();
+            enter(&mut casino, accounts.charlie, 1_000).unwrap();
+            assert_eq!(
+                enter(&mut casino, accounts.django, 1_000),
+                Err(Error::GameFull)
+            );
+            assert_eq!(enter(&mut casino, accounts.bob, 500), Ok(()));
+            assert_eq!(casino.get_participant_count(1), 2);
+        }
+
+        #[ink::test]
+        fn effective_price_follows_the_curve_and_settles_at_resolution() {
+            let mut casino = open_casino(10_000);
+            assert_eq!(casino.get_effective_price(1), Some(SCALE));
+            advance(3);
+            assert_eq!(casino.get_effective_price(1), Some(SCALE * 130 / 100));
+            advance(7);
+            assert_eq!(casino.get_effective_price(1), Some(SCALE * 2));
+            rig(&mut casino, 1, 500..=u32::MAX);
+            resolve(&mut casino);
+            assert_eq!(casino.get_effective_price(1), Some(SCALE * 2));
+            advance(3);
+            assert_eq!(casino.get_effective_price(1), Some(SCALE * 2));
+
+            rig(&mut casino, 2, 100..=150);
+            resolve(&mut casino);
+            assert!(casino.get_game(2).unwrap().crashed);
+            assert_eq!(casino.get_effective_price(2), None);
+            assert_eq!(casino.get_effective_price(3), Some(SCALE));
+            assert_eq!(casino.get_effective_price(4), None);
+        }
+
+        #[ink::test]
+        fn many_small_positions_all_exit_after_the_pool_is_drained_to_its_reserve() {
+            let mut casino = open_casino_priced(1_000, 3, 10);
+            casino.set_rounding_buffer(5).unwrap();
+            assert_eq!(casino.get_rounding_buffer(), 5);
+            let players: Vec<AccountId> = (1..=20u8).map(|id| AccountId::from([id; 32])).collect();
+            for (index, account) in players.iter().enumerate() {
+                enter(&mut casino, *account, 7 + index as Balance % 3).unwrap();
+            }
+            rig(&mut casino, 1, 500..=u32::MAX);
+            resolve(&mut casino);
+
+            set_caller(accounts().alice);
+            let free = casino.get_free_liquidity();
+            casino.withdraw(free).unwrap();
+            for account in &players {
+                assert!(casino.settle_position(1, *account).unwrap() > 0);
+            }
+            assert!(casino.get_casino_pool() >= casino.get_rounding_buffer());
+            assert!(casino.check_solvency().2);
+        }
+
+        fn topic<T: Encode>(value: &T) -> Vec<u8> {
+            let mut topic = value.encode();
+            topic.resize(32, 0);
+            topic
+        }
+
+        #[ink::test]
+        fn player_events_index_the_player_and_game() {
+            let mut casino = open_casino(10_000);
+            let bob = accounts().bob;
+            let expected = [topic(&bob), topic(&1u64)];
+            enter(&mut casino, bob, 1_000).unwrap();
+            let entered = test::recorded_events().last().unwrap();
+            assert_eq!(entered.topics[1..], expected[..]);
+            casino.exit_game(1).unwrap();
+            let requested = test::recorded_events().last().unwrap();
+            assert_eq!(requested.topics[1..], expected[..]);
+
+            rig(&mut casino, 1, 500..=u32::MAX);
+            resolve(&mut casino);
+            set_caller(bob);
+            casino.exit_game(1).unwrap();
+            let exited = test::recorded_events().last().unwrap();
+            let event = PlayerExited::decode(&mut &exited.data[..]).unwrap();
+            assert_eq!(event.payout, 1_000);
+            assert_eq!(exited.topics[1..], expected[..]);
+        }
+
+        #[ink::test]
+        fn the_conversion_rate_scales_payouts_after_the_fee() {
+            let mut casino = open_casino(10_000);
+            assert_eq!(casino.get_conversion_rate(), CONVERSION_RATE_BASE);
+            assert_eq!(
+                casino.set_conversion_rate(0),
+                Err(Error::InvalidConversionRate)
+            );
+            casino.set_house_fee(1_000).unwrap();
+            casino.set_conversion_rate(1_500_000).unwrap();
+            let accounts = accounts();
+            enter(&mut casino, accounts.bob, 1_000).unwrap();
+            enter(&mut casino, accounts.charlie, 1_000).unwrap();
+            // 1_000 less the 100 fee, paid at 1.5 units per unit.
+            assert_eq!(casino.quote_exit(1, accounts.bob), Some(1_350));
+            rig(&mut casino, 1, 500..=u32::MAX);
+            resolve(&mut casino);
+            casino.settle_position(1, accounts.bob).unwrap();
+            assert_eq!(casino.get_pending_withdrawal(accounts.bob), 2_700);
+            assert_eq!(casino.get_casino_pool(), 12_000 - 200 - 2_700);
+
+            set_caller(accounts.alice);
+            casino.set_conversion_rate(500_000).unwrap();
+            casino.settle_position(1, accounts.charlie).unwrap();
+            assert_eq!(casino.get_pending_withdrawal(accounts.charlie), 900);
+        }
+
+        #[ink::test]
+        fn exits_are_offered_only_inside_the_window() {
+            let mut casino = open_casino(10_000);
+            casino.set_max_grace_blocks(5).unwrap();
+            let accounts = accounts();
+            enter(&mut casino, accounts.bob, 1_000).unwrap();
+            assert!(casino.can_exit(1, accounts.bob));
+            assert!(!casino.can_exit(1, accounts.charlie));
+            let start = casino.get_current_game(0).unwrap().start_block;
+            advance(start + INTERVAL - block());
+            assert!(casino.can_exit(1, accounts.bob));
+            advance(1);
+            assert!(!casino.can_exit(1, accounts.bob));
+            set_caller(accounts.alice);
+            casino.tick().unwrap();
+            assert_ne!(casino.get_game_state(1), Some(GameState::Open));
+            assert!(!casino.can_exit(1, accounts.bob));
+        }
+
+        #[ink::test]
+        fn history_stays_bounded_and_evicted_games_leave_no_positions() {
+            let mut casino = open_casino(10_000);
+            casino.set_history_limit(3).unwrap();
+            casino.set_crash_probability(10_000).unwrap();
+            rig(&mut casino, 1, 500..=u32::MAX);
+            let bob = accounts().bob;
+            for game_id in 1..=8u64 {
+                enter(&mut casino, bob, 100).unwrap();
+                assert_eq!(casino.get_current_game(0).unwrap().id, game_id);
+                resolve(&mut casino);
+            }
+            // Game 1 predates the bust setting and cashed. Its unsettled position holds up
+            // eviction of everything behind it until settled.
+            assert_eq!(casino.get_game_state(1), Some(GameState::Cashed));
+            assert_eq!(casino.get_retained_window().0, 1);
+            casino.settle_position(1, bob).unwrap();
+            enter(&mut casino, bob, 100).unwrap();
+            resolve(&mut casino);
+            let (oldest, newest) = casino.get_retained_window();
+            assert_eq!(newest, 10);
+            assert_eq!(oldest, 8);
+            for game_id in 1..oldest {
+                assert!(casino.get_game(game_id).is_none());
+                assert!(casino.players.get((game_id, bob)).is_none());
+                assert_eq!(casino.get_participant_count(game_id), 0);
+                assert_eq!(casino.get_participant(game_id, 0), None);
+            }
+            assert!(casino.players.get((oldest, bob)).is_some());
+        }
+
+        #[ink::test]
+        fn stored_entropy_matches_a_rehash_of_the_published_salt() {
+            let mut casino = open_casino(10_000);
+            enter(&mut casino, accounts().bob, 1_000).unwrap();
+            resolve(&mut casino);
+            let game = casino.get_game(1).unwrap();
+            let (proof, crashed) = casino.verify_game(1).unwrap();
+            assert_eq!(crashed, game.crashed);
+            assert_eq!(proof.resolution_entropy, game.resolution_entropy);
+            let mut hash = [0u8; 32];
+            ink::env::hash_bytes::<ink::env::hash::Blake2x256>(&proof.salt, &mut hash);
+            assert_eq!(
+                game.resolution_entropy,
+                u32::from_be_bytes([hash[0], hash[1], hash[2], hash[3]])
+            );
+            assert_eq!(
+                game.crash_threshold,
+                CrashCasino::crash_point(game.resolution_entropy)
+            );
+        }
+
+        #[ink::test]
+        fn games_wait_for_the_minimum_pool_and_resume_once_funded() {
+            let mut casino = open_casino(1_000);
+            casino.set_min_pool_to_start(5_000).unwrap();
+            resolve(&mut casino);
+            assert_eq!(casino.get_total_games(), 1);
+            let event: StartDeferred = last_event();
+            assert_eq!(event.casino_pool, 1_000);
+            assert_eq!(event.required, 5_000);
+            advance(1);
+            casino.tick().unwrap();
+            assert_eq!(casino.get_total_games(), 1);
+
+            set_value(4_000);
+            casino.fund_pool().unwrap();
+            set_value(0);
+            advance(1);
+            casino.tick().unwrap();
+            assert_eq!(casino.get_total_games(), 2);
+            assert_eq!(casino.get_current_game(0).unwrap().state, GameState::Open);
+        }
+
+        #[ink::test]
+        fn a_fee_free_game_runs_between_normal_games() {
+            let mut casino = open_casino(10_000);
+            casino.set_house_fee(1_000).unwrap();
+            let accounts = accounts();
+            set_caller(accounts.charlie);
+            assert_eq!(casino.set_game_fee(2, Some(0)), Err(Error::NotAdmin));
+            set_caller(accounts.alice);
+            casino.grant_admin(accounts.charlie).unwrap();
+            set_caller(accounts.charlie);
+            assert_eq!(casino.set_game_fee(2, Some(10_001)), Err(Error::InvalidFee));
+            casino.set_game_fee(2, Some(0)).unwrap();
+
+            let mut paid = Vec::new();
+            for game_id in 1..=3u64 {
+                rig(&mut casino, game_id, 500..=u32::MAX);
+                enter(&mut casino, accounts.bob, 1_000).unwrap();
+                casino.exit_game(game_id).unwrap();
+                resolve(&mut casino);
+                let before = casino.get_pending_withdrawal(accounts.bob);
+                set_caller(accounts.bob);
+                casino.exit_game(game_id).unwrap();
+                paid.push(casino.get_pending_withdrawal(accounts.bob) - before);
+            }
+            assert_eq!(paid, vec![900, 1_000, 900]);
+        }
+
+        #[ink::test]
+        fn admins_manage_the_operational_settings() {
+            let mut casino = open_casino(0);
+            let accounts = accounts();
+            casino.grant_admin(accounts.charlie).unwrap();
+            set_caller(accounts.bob);
+            assert_eq!(casino.set_keeper_reward(1), Err(Error::NotAdmin));
+            assert_eq!(casino.set_block_contracts(true), Err(Error::NotAdmin));
+            assert_eq!(casino.set_min_pool_to_start(1), Err(Error::NotAdmin));
+            set_caller(accounts.charlie);
+            casino.set_keeper_reward(1).unwrap();
+            casino.set_block_contracts(true).unwrap();
+            casino.set_min_pool_to_start(1).unwrap();
+            assert_eq!(casino.get_keeper_reward(), 1);
+            assert!(casino.get_block_contracts());
+            assert_eq!(casino.get_min_pool_to_start(), 1);
+        }
+
+        #[ink::test]
+        fn contract_callers_are_rejected_only_when_enabled() {
+            let mut casino = open_casino(10_000);
+            let accounts = accounts();
+            test::set_contract::<DefaultEnvironment>(accounts.frank);
+            assert!(!casino.get_block_contracts());
+            assert_eq!(enter(&mut casino, accounts.frank, 1_000), Ok(()));
+
+            set_caller(accounts.alice);
+            casino.set_block_contracts(true).unwrap();
+            assert_eq!(
+                enter(&mut casino, accounts.frank, 1_000),
+                Err(Error::ContractsNotAllowed)
+            );
+            assert_eq!(enter(&mut casino, accounts.bob, 1_000), Ok(()));
+        }
+
+        #[ink::test]
+        fn a_losing_streak_earns_a_rebate_and_starts_over() {
+            let mut casino = open_casino(10_000);
+            casino.set_loss_rebate(2, 50).unwrap();
+            set_value(200);
+            casino.fund_rebate_pool().unwrap();
+            set_value(0);
+            let bob = accounts().bob;
+            let mut streaks = Vec::new();
+            for game_id in 1..=4u64 {
+                enter(&mut casino, bob, 100).unwrap();
+                rig(&mut casino, game_id, 100..=150);
+                resolve(&mut casino);
+                casino.settle_position(game_id, bob).unwrap();
+                streaks.push(casino.get_loss_streak(bob));
+            }
+            assert_eq!(streaks, vec![1, 2, 0, 1]);
+            assert_eq!(casino.get_pending_withdrawal(bob), 50);
+            assert_eq!(casino.get_rebate_pool(), 150);
+
+            enter(&mut casino, bob, 100).unwrap();
+            rig(&mut casino, 5, 500..=u32::MAX);
+            resolve(&mut casino);
+            casino.settle_position(5, bob).unwrap();
+            assert_eq!(casino.get_loss_streak(bob), 0);
+        }
+
+        #[ink::test]
+        fn every_loser_is_counted_however_many_entered() {
+            let mut casino = open_casino(100_000);
+            let players: Vec<AccountId> = (1..=MAX_BATCH_SIZE as u8 + 5)
+                .map(|id| AccountId::from([id; 32]))
+                .collect();
+            for account in &players {
+                enter(&mut casino, *account, 10).unwrap();
+            }
+            rig(&mut casino, 1, 100..=150);
+            resolve(&mut casino);
+            set_caller(accounts().alice);
+            advance(SWEEP_DELAY);
+            while casino.get_participant_count(1) > 0 {
+                casino.sweep_game(1).unwrap();
+            }
+            for account in &players {
+                assert_eq!(casino.get_loss_streak(*account), 1);
+            }
+        }
+
+        fn schedule(
+            casino: &mut CrashCasino,
+            account: AccountId,
+            count: u8,
+            amount_each: Balance,
+        ) -> Result<(), Error> {
+            set_caller(account);
+            set_value(amount_each * count as Balance);
+            let result = casino.schedule_entries(0, count, amount_each);
+            set_value(0);
+            result
+        }
+
+        #[ink::test]
+        fn scheduled_entries_run_across_three_games() {
+            let mut casino = open_casino(10_000);
+            let bob = accounts().bob;
+            schedule(&mut casino, bob, 3, 100).unwrap();
+            for game_id in 2..=5u64 {
+                resolve(&mut casino);
+                let entered = casino.players.get((game_id, bob)).is_some();
+                assert_eq!(entered, game_id <= 4, "game {game_id}");
+            }
+            assert_eq!(casino.get_entry_schedule(0, bob), None);
+        }
+
+        #[ink::test]
+        fn scheduled_entries_wait_for_the_entry_delay() {
+            let mut casino = open_casino(10_000);
+            casino.set_entry_delay(2).unwrap();
+            let bob = accounts().bob;
+            schedule(&mut casino, bob, 1, 100).unwrap();
+            resolve(&mut casino);
+            assert!(casino.players.get((2, bob)).is_none());
+            advance(2);
+            casino.tick().unwrap();
+            assert!(casino.players.get((2, bob)).is_none());
+            advance(1);
+            casino.tick().unwrap();
+            assert_eq!(casino.players.get((2, bob)).unwrap().total_deposited, 100);
+        }
+
+        #[ink::test]
+        fn the_contract_check_applies_to_the_scheduling_account() {
+            let mut casino = open_casino(10_000);
+            let accounts = accounts();
+            test::set_contract::<DefaultEnvironment>(accounts.frank);
+            test::set_contract::<DefaultEnvironment>(accounts.eve);
+            schedule(&mut casino, accounts.frank, 1, 100).unwrap();
+            schedule(&mut casino, accounts.bob, 1, 100).unwrap();
+            set_caller(accounts.alice);
+            casino.set_block_contracts(true).unwrap();
+            assert_eq!(
+                schedule(&mut casino, accounts.eve, 1, 100),
+                Err(Error::ContractsNotAllowed)
+            );
+
+            // A contract keeper ticking does not stop an ordinary account's entry.
+            set_caller(accounts.alice);
+            casino.set_keeper(Some(accounts.eve)).unwrap();
+            advance(INTERVAL);
+            set_caller(accounts.eve);
+            casino.tick().unwrap();
+            assert!(casino.players.get((2, accounts.bob)).is_some());
+            assert!(casino.players.get((2, accounts.frank)).is_none());
+        }
+    }
+
+    #[cfg(all(test, feature = "e2e-tests"))]
+    mod e2e_tests {
+        use super::*;
+        use ink_e2e::ContractsBackend;
+
+        type E2EResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+        fn topic<T: Encode>(value: &T) -> [u8; 32] {
+            let mut topic = [0u8; 32];
+            let encoded = value.encode();
+            topic[..encoded.len()].copy_from_slice(&encoded);
+            topic
+        }
+
+        #[ink_e2e::test]
+        async fn player_events_carry_player_and_game_topics(
+            mut client: ink_e2e::Client<C, E>,
+        ) -> E2EResult<()> {
+            let mut constructor = CrashCasinoRef::new(100, None, 1_000_000, 1_000_000);
+            let contract = client
+                .instantiate("contract", &ink_e2e::alice(), &mut constructor)
+                .submit()
+                .await
+                .expect("instantiate failed");
+            let mut call_builder = contract.call_builder::<CrashCasino>();
+            client
+                .call(&ink_e2e::alice(), &call_builder.start_game(0))
+                .submit()
+                .await
+                .expect("start_game failed");
+
+            let bob = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
+            let expected = [topic(&bob), topic(&1u64)];
+            let entry = client
+                .call(&ink_e2e::bob(), &call_builder.enter_game(0))
+                .value(1_000)
+                .submit()
+                .await
+                .expect("enter_game failed");
+            let exit = client
+                .call(&ink_e2e::bob(), &call_builder.exit_game(1))
+                .submit()
+                .await
+                .expect("exit_game failed");
+            for result in [
+                entry.contract_emitted_events()?,
+                exit.contract_emitted_events()?,
+            ] {
+                let event = result.last().expect("a player event was emitted");
+                let topics: Vec<[u8; 32]> = event.topics[1..].iter().map(|topic| topic.0).collect();
+                assert_eq!(topics, expected);
+            }
+            Ok(())
+        }
+    }
 }